@@ -1,13 +1,17 @@
+use std::marker::PhantomData;
+
 use bevy::{
     asset::{AssetLoader, AsyncReadExt},
     prelude::*,
+    utils::HashMap,
 };
 use bevy_asset_loader::prelude::*;
 use serde::Deserialize;
 use thiserror::Error;
 
 use crate::{
-    game::{AttentionType, GameState, ItemRequest},
+    game::{AttentionType, Consequences, Effect, GameState, ItemRequest, ItemType},
+    utils::{Ratios, Total},
     AppState,
 };
 
@@ -51,14 +55,61 @@ pub struct Sounds {
 
 #[derive(AssetCollection, Resource)]
 pub struct Characters {
-    #[asset(path = "customers/dumb.chr.ron")]
-    pub dumb: Handle<CharacterTraits>,
-    #[asset(path = "customers/attentive.chr.ron")]
-    pub attentive: Handle<CharacterTraits>,
-    #[asset(path = "customers/normal.chr.ron")]
-    pub normal: Handle<CharacterTraits>,
-    #[asset(path = "customers/cop.chr.ron")]
-    pub cop: Handle<CharacterTraits>,
+    #[asset(
+        paths(
+            "customers/dumb.chr.ron",
+            "customers/attentive.chr.ron",
+            "customers/normal.chr.ron",
+            "customers/cop.chr.ron"
+        ),
+        collection(typed)
+    )]
+    pub all: Vec<Handle<CharacterTraits>>,
+}
+
+impl Characters {
+    /// Find a character by the name in their file
+    pub fn named(
+        &self,
+        name: &str,
+        traits: &Assets<CharacterTraits>,
+    ) -> Option<Handle<CharacterTraits>> {
+        self.all
+            .iter()
+            .find(|h| traits.get(*h).is_some_and(|t| t.name == name))
+            .cloned()
+    }
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct GameData {
+    #[asset(path = "goods.items.ron")]
+    pub items: Handle<ItemData>,
+    #[asset(path = "shop.recipes.ron")]
+    pub recipes: Handle<RecipeBook>,
+    #[asset(path = "shop.upgrades.ron")]
+    pub upgrades: Handle<UpgradeList>,
+    #[asset(path = "story.campaign.ron")]
+    pub campaign: Handle<Campaign>,
+    #[asset(path = "day.arrivals.ron")]
+    pub arrivals: Handle<ArrivalCurve>,
+    #[asset(path = "day.events.ron")]
+    pub events: Handle<DailyEvents>,
+    #[asset(path = "day.lighting.ron")]
+    pub lighting: Handle<DayLighting>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct Difficulties {
+    #[asset(
+        paths(
+            "difficulties/easy.diff.ron",
+            "difficulties/normal.diff.ron",
+            "difficulties/hard.diff.ron"
+        ),
+        collection(typed)
+    )]
+    pub presets: Vec<Handle<Difficulty>>,
 }
 
 #[derive(AssetCollection, Resource)]
@@ -72,7 +123,25 @@ pub struct AssetPlugin;
 impl Plugin for AssetPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<CharacterTraits>()
-            .init_asset_loader::<CharacteristicsLoader>()
+            .init_asset::<ItemData>()
+            .init_asset::<RecipeBook>()
+            .init_asset::<UpgradeList>()
+            .init_asset::<Difficulty>()
+            .init_asset::<Campaign>()
+            .init_asset::<ArrivalCurve>()
+            .init_asset::<DailyEvents>()
+            .init_asset::<DayLighting>()
+            .register_asset_loader(RonLoader::<CharacterTraits>::new(&[
+                "chr.ron", "chr", "char.ron", "char",
+            ]))
+            .register_asset_loader(RonLoader::<ItemData>::new(&["items.ron"]))
+            .register_asset_loader(RonLoader::<RecipeBook>::new(&["recipes.ron"]))
+            .register_asset_loader(RonLoader::<UpgradeList>::new(&["upgrades.ron"]))
+            .register_asset_loader(RonLoader::<Difficulty>::new(&["diff.ron"]))
+            .register_asset_loader(RonLoader::<Campaign>::new(&["campaign.ron"]))
+            .register_asset_loader(RonLoader::<ArrivalCurve>::new(&["arrivals.ron"]))
+            .register_asset_loader(RonLoader::<DailyEvents>::new(&["events.ron"]))
+            .register_asset_loader(RonLoader::<DayLighting>::new(&["lighting.ron"]))
             .add_loading_state(
                 LoadingState::new(AppState::Load)
                     .continue_to_state(AppState::Done)
@@ -88,7 +157,9 @@ impl Plugin for AssetPlugin {
             .add_collection_to_loading_state::<_, Meshes>(GameState::Loading)
             .add_collection_to_loading_state::<_, Images>(GameState::Loading)
             .add_collection_to_loading_state::<_, Sounds>(GameState::Loading)
-            .add_collection_to_loading_state::<_, Characters>(GameState::Loading);
+            .add_collection_to_loading_state::<_, Characters>(GameState::Loading)
+            .add_collection_to_loading_state::<_, GameData>(GameState::Loading)
+            .add_collection_to_loading_state::<_, Difficulties>(GameState::Loading);
     }
 }
 
@@ -111,16 +182,360 @@ pub struct CharacterTraits {
     pub accept: String,
     pub reject: String,
     pub accuse: String,
+    /// Said when paying for goods that are past their best
+    pub stale: String,
+    /// Said when refusing goods that have gone off
+    pub spoiled: String,
+    /// Said when they can't afford any of what they came for
+    pub broke: String,
+    /// Said when they give up haggling and leave
+    pub walkout: String,
+    /// Asked when the haggling starts
+    pub ask_price: String,
+    /// Said to an offer that's too dear, with `{offer}` and `{counter}` filled in
+    pub counter_offer: String,
     pub request: Vec<ItemRequest>,
     pub attention_type: AttentionType,
     pub rep_hit: u8,
+    /// How hard the character bargains, from 0 to 1
+    pub stinginess: f32,
+    /// Odds of the character slipping clipped or fake coins into their payment
+    #[serde(default)]
+    pub counterfeit: f32,
+    /// Least and most gold the character carries
+    pub purse: (f32, f32),
+    /// What happens when this character catches the player cheating
+    #[serde(default)]
+    pub caught: Consequences,
+}
+
+// item files
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct ItemInfo {
+    /// How much freshness a batch loses every day
+    pub decay: f32,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+pub struct ItemData {
+    pub items: HashMap<ItemType, ItemInfo>,
+}
+
+impl ItemData {
+    pub fn decay(&self, t: ItemType) -> f32 {
+        self.items.get(&t).map(|i| i.decay).unwrap_or_default()
+    }
+}
+
+// recipe files
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Recipe {
+    pub name: String,
+    /// Grams of each ingredient that go into a single product
+    pub ingredients: HashMap<ItemType, f32>,
+    /// What a customer pays for one, before its quality is taken into account
+    pub price: f32,
+    /// Seconds it takes to brew a single product
+    pub time: f32,
+}
+
+impl Recipe {
+    /// Grams of ingredients needed for a single product
+    pub fn weight(&self) -> f32 {
+        self.ingredients.total()
+    }
+
+    /// Whether `contents` has exactly the ingredients of this recipe, in any amount
+    pub fn uses(&self, contents: &HashMap<ItemType, f32>) -> bool {
+        contents.len() == self.ingredients.len()
+            && contents.keys().all(|t| self.ingredients.contains_key(t))
+    }
+
+    /// How close the ratio of `contents` is to the recipe, from 0.0 to 1.0
+    pub fn accuracy(&self, contents: &HashMap<ItemType, f32>) -> f32 {
+        let wanted = self.ingredients.ratio();
+        let actual = contents.ratio();
+        let off: f32 = wanted
+            .iter()
+            .map(|(t, r)| (r - actual.get(t).unwrap_or(&0.0)).abs())
+            .sum();
+
+        1.0 - off / 2.0
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+pub struct RecipeBook {
+    pub recipes: Vec<Recipe>,
+}
+
+impl RecipeBook {
+    pub fn get(&self, name: &str) -> Option<&Recipe> {
+        self.recipes.iter().find(|r| r.name == name)
+    }
+
+    /// The recipe that can be brewed out of `contents`
+    pub fn matching(&self, contents: &HashMap<ItemType, f32>) -> Option<&Recipe> {
+        self.recipes.iter().find(|r| r.uses(contents))
+    }
+}
+
+// upgrade files
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Upgrade {
+    /// What the upgrade is remembered as in the save file
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub cost: f32,
+    pub effect: Effect,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+pub struct UpgradeList {
+    pub upgrades: Vec<Upgrade>,
+}
+
+impl UpgradeList {
+    pub fn get(&self, id: &str) -> Option<&Upgrade> {
+        self.upgrades.iter().find(|u| u.id == id)
+    }
+}
+
+// difficulty files
+
+#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+pub struct Difficulty {
+    pub name: String,
+    /// Multiplies the stall rent due at the end of every week
+    pub rent_factor: f32,
+    /// Share of each day's income taken by the market
+    pub tax: f32,
+    /// Multiplies how likely customers are to get distracted
+    pub distraction: f32,
+    /// Multiplies how far off an order can be before customers notice
+    pub threshold: f32,
+    /// Multiplies the reputation each customer gives or takes
+    pub rep_hit: f32,
+    /// Range of seconds between customers
+    pub customer_wait: (f32, f32),
+    /// Length of a day in seconds
+    pub day_len: f32,
+    /// Reputation every game starts out with
+    pub reputation: u8,
+    /// Fine for cheat weights the market inspector finds
+    pub inspection_fine: f32,
+    /// Reputation lost when the inspector finds cheat weights
+    pub inspection_penalty: i32,
+    /// Reputation gained for passing an inspection
+    pub inspection_bonus: i32,
+}
+
+impl Difficulty {
+    /// A customer's reputation hit, scaled to this difficulty
+    pub fn rep_hit(&self, base: u8) -> u8 {
+        (base as f32 * self.rep_hit).round() as u8
+    }
 }
 
-#[derive(Default)]
-pub struct CharacteristicsLoader;
+// campaign files
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SpawnTier {
+    /// Lowest reputation these customers show up at
+    pub min_rep: u8,
+    /// Character names, as written in their files
+    pub customers: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Week {
+    pub name: String,
+    pub days: usize,
+    /// Profit needed by the end of the week to carry on
+    pub goal: f32,
+    pub rent: f32,
+    /// Characters making their first appearance, announced before the week starts
+    pub introduces: Vec<String>,
+    pub spawns: Vec<SpawnTier>,
+}
+
+impl Week {
+    /// Names of the customers that show up at this reputation
+    pub fn customers(&self, rep: u8) -> &[String] {
+        self.spawns
+            .iter()
+            .filter(|t| t.min_rep <= rep)
+            .max_by_key(|t| t.min_rep)
+            .map(|t| t.customers.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+pub struct Campaign {
+    pub weeks: Vec<Week>,
+}
+
+/// A point on a curve over the day, blended with its neighbours in between
+trait Keyframe: Copy {
+    /// Share of the day gone by
+    fn at(&self) -> f32;
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+/// Where the curve is at this point in the day, between the nearest keys
+fn sample<K: Keyframe>(keys: &[K], progress: f32) -> Option<K> {
+    let after = keys.iter().position(|k| k.at() >= progress);
+    match after {
+        Some(0) => keys.first().copied(),
+        Some(i) => {
+            let (a, b) = (keys[i - 1], keys[i]);
+            Some(a.lerp(&b, (progress - a.at()) / (b.at() - a.at())))
+        }
+        None => keys.last().copied(),
+    }
+}
+
+// arrival files
+
+#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+pub struct ArrivalCurve {
+    /// Share of the day gone by, and how many customers turn up compared to usual
+    pub keys: Vec<(f32, f32)>,
+}
+
+impl Keyframe for (f32, f32) {
+    fn at(&self) -> f32 {
+        self.0
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        (
+            self.0 + (other.0 - self.0) * t,
+            self.1 + (other.1 - self.1) * t,
+        )
+    }
+}
+
+impl ArrivalCurve {
+    /// How busy the stall is at this point in the day
+    pub fn rate(&self, progress: f32) -> f32 {
+        sample(&self.keys, progress).map_or(1.0, |(_, r)| r)
+    }
+}
+
+// event files
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum EventEffect {
+    /// Multiplies how many customers turn up
+    Turnout(f32),
+    /// Freshness lost by everything sold by weight
+    Soak(f32),
+    /// Extra cops walking the market
+    Cops(usize),
+    /// Multiplies the price of one good, picked on the day
+    Shortage(f32),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DailyEvent {
+    pub name: String,
+    /// Written up in the morning paper, `{good}` is filled in for shortages
+    pub headline: String,
+    pub story: String,
+    /// How likely this is compared to the other events
+    pub weight: f32,
+    pub effects: Vec<EventEffect>,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+pub struct DailyEvents {
+    /// How likely a day is to go by without anything happening
+    pub quiet: f32,
+    pub events: Vec<DailyEvent>,
+}
+
+// lighting files
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct LightingKey {
+    /// Share of the day gone by
+    pub at: f32,
+    /// Degrees the sun is above the horizon
+    pub sun_height: f32,
+    pub sun_color: Color,
+    pub illuminance: f32,
+    pub ambient: f32,
+    pub fog_color: Color,
+    pub fog_density: f32,
+}
+
+impl Keyframe for LightingKey {
+    fn at(&self) -> f32 {
+        self.at
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        let mix_color = |a: Color, b: Color| {
+            Color::rgba(
+                mix(a.r(), b.r()),
+                mix(a.g(), b.g()),
+                mix(a.b(), b.b()),
+                mix(a.a(), b.a()),
+            )
+        };
+        Self {
+            at: mix(self.at, other.at),
+            sun_height: mix(self.sun_height, other.sun_height),
+            sun_color: mix_color(self.sun_color, other.sun_color),
+            illuminance: mix(self.illuminance, other.illuminance),
+            ambient: mix(self.ambient, other.ambient),
+            fog_color: mix_color(self.fog_color, other.fog_color),
+            fog_density: mix(self.fog_density, other.fog_density),
+        }
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+pub struct DayLighting {
+    pub keys: Vec<LightingKey>,
+    /// Share of the day gone by when the lantern gets lit
+    pub lantern: f32,
+}
+
+impl DayLighting {
+    /// How the stall is lit at this point in the day
+    pub fn at(&self, progress: f32) -> Option<LightingKey> {
+        sample(&self.keys, progress)
+    }
+}
+
+/// Loads any asset that can be deserialized straight from RON
+pub struct RonLoader<T> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> RonLoader<T> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            _marker: PhantomData,
+        }
+    }
+}
 
-impl AssetLoader for CharacteristicsLoader {
-    type Asset = CharacterTraits;
+impl<T> AssetLoader for RonLoader<T>
+where
+    T: Asset + for<'de> Deserialize<'de>,
+{
+    type Asset = T;
 
     type Settings = ();
 
@@ -141,6 +556,6 @@ impl AssetLoader for CharacteristicsLoader {
     }
 
     fn extensions(&self) -> &[&str] {
-        &["chr.ron", "chr", "char.ron", "char"]
+        self.extensions
     }
 }
@@ -1,29 +1,113 @@
 #![allow(dead_code)]
 use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
 use bevy_tweening::{lens::TransformPositionLens, *};
 use rand::prelude::*;
 
-use crate::game::GameState;
+use crate::{
+    assets::CharacterTraits,
+    game::{AvailableCustomers, DayTimer, GameState, Reputation, Turnout},
+};
 
 pub const CROWD_SIZE: u8 = 10;
+const MIN_CROWD: usize = 3;
+const MAX_CROWD: usize = 20;
+/// Seconds between new people turning up on the road
+const ARRIVAL_SECS: f32 = 0.5;
 
+/// Someone walking along the road, with what kind of customer they'd be if they stopped
 #[derive(Component)]
-pub struct CrowdMember;
+pub struct CrowdMember(pub Option<Handle<CharacterTraits>>);
 
 pub const ROAD_OFFSET: f32 = -4.125;
 
 #[derive(Resource, Default, Deref, DerefMut, Clone, Debug)]
 pub struct CrowdTextures(Vec<Handle<StandardMaterial>>);
 
+#[derive(Resource, Default, Clone, Debug)]
+pub struct CrowdMesh(Handle<Mesh>);
+
+/// One material per kind of customer, shared by everyone of that kind on the road
+#[derive(Resource, Default, Deref, DerefMut, Clone, Debug)]
+struct CharacterMaterials(HashMap<Handle<CharacterTraits>, Handle<StandardMaterial>>);
+
 pub struct CrowdPlugin;
 
 impl Plugin for CrowdPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<CrowdTextures>()
+            .init_resource::<CrowdMesh>()
+            .init_resource::<CharacterMaterials>()
             .add_systems(OnEnter(GameState::MainMenu), spawn_crowd)
-            .add_systems(Update, randomize_crowd);
+            .add_systems(Update, (randomize_crowd, fill_crowd));
+    }
+}
+
+/// How busy the road is over the day, thin in the morning and evening
+pub fn busyness(progress: f32) -> f32 {
+    1.0 - 0.4 * (2.0 * progress - 1.0).powi(2)
+}
+
+/// Everything that decides how busy the road should be
+#[derive(SystemParam)]
+struct Traffic<'w> {
+    rep: Res<'w, Reputation>,
+    turnout: Res<'w, Turnout>,
+    day: Res<'w, DayTimer>,
+}
+
+impl Traffic<'_> {
+    /// How many people should be on the road, a well liked stall draws a bigger crowd
+    fn crowd_size(&self) -> usize {
+        let size =
+            CROWD_SIZE as f32 * self.rep.pull() * **self.turnout * busyness(self.day.percent());
+        (size.round() as usize).clamp(MIN_CROWD, MAX_CROWD)
+    }
+}
+
+/// Who could come walking down the road
+#[derive(SystemParam)]
+struct Passers<'w> {
+    available: Res<'w, AvailableCustomers>,
+    chars: Res<'w, Assets<CharacterTraits>>,
+    textures: Res<'w, CrowdTextures>,
+    cached: ResMut<'w, CharacterMaterials>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+}
+
+impl Passers<'_> {
+    /// Who the next person on the road is, some are only passing through
+    fn next(
+        &mut self,
+        rng: &mut impl Rng,
+        traffic: &Traffic,
+    ) -> (CrowdMember, Handle<StandardMaterial>) {
+        // more people are out shopping around midday
+        let shopper = rng.gen_bool(busyness(traffic.day.percent()) as f64);
+        let traits = self
+            .available
+            .choose(rng)
+            .filter(|_| shopper)
+            .and_then(|h| self.chars.get(h).map(|t| (h, t)));
+
+        match traits {
+            Some((handle, traits)) => {
+                let material = self
+                    .cached
+                    .entry(handle.clone())
+                    .or_insert_with(|| self.materials.add(traits.color.into()))
+                    .clone();
+                (CrowdMember(Some(handle.clone())), material)
+            }
+            None => (
+                CrowdMember(None),
+                self.textures
+                    .choose(rng)
+                    .expect("No customer textures")
+                    .clone(),
+            ),
+        }
     }
 }
 
@@ -32,8 +116,9 @@ fn spawn_crowd(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut textures: ResMut<CrowdTextures>,
+    mut mesh: ResMut<CrowdMesh>,
 ) {
-    let mesh = meshes.add(shape::Box::new(1.0, 1.5, 0.0).into());
+    mesh.0 = meshes.add(shape::Box::new(1.0, 1.5, 0.0).into());
 
     for c in [
         Color::RED,
@@ -51,22 +136,11 @@ fn spawn_crowd(
     for i in 0..CROWD_SIZE {
         let offset = rng.gen_range(-0.5..=0.5) + ROAD_OFFSET;
         let start: f32 = *[-10.0, 10.0].choose(&mut rng).expect("????");
-        let end: f32 = -10.0 * start.signum();
-        let anim = Tween::new(
-            EaseMethod::Linear,
-            Duration::from_secs(rng.gen_range(2..=5)),
-            TransformPositionLens {
-                start: Vec3::new(start, 1.0, offset),
-                end: Vec3::new(end, 1.0, offset),
-            },
-        )
-        .with_repeat_strategy(RepeatStrategy::MirroredRepeat)
-        .with_repeat_count(RepeatCount::Infinite)
-        .with_completed_event(i as u64);
+        let anim = stroll(&mut rng, start, offset, i as u64);
 
         cmd.spawn((
             PbrBundle {
-                mesh: mesh.clone(),
+                mesh: mesh.0.clone(),
                 material: textures
                     .choose(&mut rng)
                     .expect("No customer textures")
@@ -74,29 +148,94 @@ fn spawn_crowd(
                 transform: Transform::from_xyz(10.0, 1.0, offset),
                 ..default()
             },
-            CrowdMember,
+            CrowdMember(None),
             Animator::new(anim),
         ));
     }
 }
 
+/// Walking up and down the road, from `start` to the other end and back
+pub fn stroll(rng: &mut impl Rng, start: f32, offset: f32, id: u64) -> Tween<Transform> {
+    let end: f32 = -10.0 * start.signum();
+    Tween::new(
+        EaseMethod::Linear,
+        Duration::from_secs(rng.gen_range(2..=5)),
+        TransformPositionLens {
+            start: Vec3::new(start, 1.0, offset),
+            end: Vec3::new(end, 1.0, offset),
+        },
+    )
+    .with_repeat_strategy(RepeatStrategy::MirroredRepeat)
+    .with_repeat_count(RepeatCount::Infinite)
+    .with_completed_event(id)
+}
+
+/// Brings new people onto the road whenever the crowd is thinner than it should be
+fn fill_crowd(
+    mut cmd: Commands,
+    q: Query<(), With<CrowdMember>>,
+    mesh: Res<CrowdMesh>,
+    mut passers: Passers,
+    traffic: Traffic,
+    time: Res<Time>,
+    mut timer: Local<Timer>,
+) {
+    if timer.mode() != TimerMode::Repeating {
+        *timer = Timer::from_seconds(ARRIVAL_SECS, TimerMode::Repeating);
+    }
+    // nobody to fill in before the road is set up
+    if !timer.tick(time.delta()).just_finished() || passers.textures.is_empty() {
+        return;
+    }
+
+    let count = q.iter().count();
+    if count >= traffic.crowd_size() {
+        return;
+    }
+
+    // who walks by is only for show, so it stays off the run's rolls
+    let mut rng = thread_rng();
+    let offset = rng.gen_range(-0.5..=0.5) + ROAD_OFFSET;
+    let start: f32 = *[-10.0, 10.0].choose(&mut rng).expect("????");
+    let (member, material) = passers.next(&mut rng, &traffic);
+
+    cmd.spawn((
+        PbrBundle {
+            mesh: mesh.0.clone(),
+            material,
+            transform: Transform::from_xyz(start, 1.0, offset),
+            ..default()
+        },
+        member,
+        Animator::new(stroll(&mut rng, start, offset, count as u64)),
+    ));
+}
+
 fn randomize_crowd(
     mut cmd: Commands,
     mut events: EventReader<TweenCompleted>,
     mut anims: Query<&mut Animator<Transform>, With<CrowdMember>>,
-    textures: Res<CrowdTextures>,
+    crowd_q: Query<(), With<CrowdMember>>,
+    mut passers: Passers,
+    traffic: Traffic,
 ) {
-    let mut rng = SmallRng::from_entropy();
+    let mut rng = thread_rng();
+    let mut count = crowd_q.iter().count();
+
     for e in events.read() {
         let Ok(mut animator) = anims.get_mut(e.entity) else {
             continue;
         };
+
+        // people leave the road at either end when it's getting quiet
+        if count > traffic.crowd_size() {
+            cmd.entity(e.entity).despawn_recursive();
+            count -= 1;
+            continue;
+        }
+
         animator.set_speed(rng.gen_range(0.5..=1.5));
-        cmd.entity(e.entity).insert(
-            textures
-                .choose(&mut rng)
-                .expect("Textures were empty")
-                .clone(),
-        );
+        let (member, material) = passers.next(&mut rng, &traffic);
+        cmd.entity(e.entity).insert((member, material));
     }
 }
@@ -0,0 +1,226 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    assets::{Campaign, CharacterTraits, Characters, GameData, Week},
+    save::SaveData,
+};
+
+use super::{
+    daily_events::TodaysEvent,
+    endless::{ATTENTIVE_EVERY, COP_EVERY},
+    AvailableCustomers, DailyExpenses, DailyGold, DayIndex, DaysSurvived, GameMode, GameOutcome,
+    GameState, Ledger, Reputation,
+};
+
+/// Which week of the campaign is being played
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut, Default)]
+pub struct WeekIndex(usize);
+
+/// Profit made so far this week, dues included
+#[derive(Resource, Default, Deref, DerefMut, Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct WeekProfit(f32);
+
+/// Move on from the week end summary
+#[derive(Event, Default, Debug, Clone, Copy)]
+pub struct NextWeek;
+
+impl From<ListenerInput<Pointer<Down>>> for NextWeek {
+    fn from(_: ListenerInput<Pointer<Down>>) -> Self {
+        Self
+    }
+}
+
+/// The campaign week being played, looked up from the campaign asset
+#[derive(SystemParam)]
+pub struct CurrentWeek<'w> {
+    index: Res<'w, WeekIndex>,
+    data: Res<'w, GameData>,
+    campaigns: Res<'w, Assets<Campaign>>,
+}
+
+impl CurrentWeek<'_> {
+    pub fn get(&self) -> Option<&Week> {
+        self.campaigns
+            .get(&self.data.campaign)?
+            .weeks
+            .get(**self.index)
+    }
+
+    /// The week after this one, if the campaign goes on
+    pub fn next(&self) -> Option<&Week> {
+        self.campaigns
+            .get(&self.data.campaign)?
+            .weeks
+            .get(**self.index + 1)
+    }
+
+    /// Week number as shown to the player
+    pub fn number(&self) -> usize {
+        **self.index + 1
+    }
+
+    /// Whether the last day of the week is done
+    pub fn is_over(&self, day: &DayIndex) -> bool {
+        match self.get() {
+            Some(week) => **day >= week.days,
+            None => true,
+        }
+    }
+}
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WeekIndex>()
+            .init_resource::<WeekProfit>()
+            .add_event::<NextWeek>()
+            .add_systems(OnEnter(GameState::Reset), reset)
+            .add_systems(
+                Update,
+                (
+                    set_available_cust.run_if(
+                        resource_exists::<Characters>().and_then(
+                            resource_changed::<Reputation>()
+                                .or_else(resource_changed::<WeekIndex>())
+                                .or_else(resource_changed::<DaysSurvived>())
+                                .or_else(resource_changed::<TodaysEvent>()),
+                        ),
+                    ),
+                    next_week.run_if(in_state(GameState::WeekEnd)),
+                ),
+            );
+    }
+}
+
+/// Pick the campaign back up from the saved week, endless runs repeat the last one
+fn reset(
+    mut week: ResMut<WeekIndex>,
+    mut profit: ResMut<WeekProfit>,
+    mode: Res<GameMode>,
+    save: Res<SaveData>,
+    data: Res<GameData>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    let weeks = campaigns
+        .get(&data.campaign)
+        .map(|c| c.weeks.len())
+        .unwrap_or_default();
+
+    let last = weeks.saturating_sub(1);
+    **week = match *mode {
+        GameMode::Campaign => save.campaign_week.min(last),
+        GameMode::Endless => last,
+    };
+    **profit = 0.0;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn set_available_cust(
+    mut available: ResMut<AvailableCustomers>,
+    rep: Res<Reputation>,
+    week: CurrentWeek,
+    mode: Res<GameMode>,
+    days: Res<DaysSurvived>,
+    today: Res<TodaysEvent>,
+    customers: Res<Characters>,
+    traits: Res<Assets<CharacterTraits>>,
+) {
+    let Some(week) = week.get() else {
+        error!("Campaign asset was missing");
+        return;
+    };
+
+    let mut names: Vec<&str> = week.customers(**rep).iter().map(String::as_str).collect();
+
+    // the crowd gets harder to fool the longer an endless run goes on
+    if *mode == GameMode::Endless {
+        names.extend(vec!["attentive"; **days / ATTENTIVE_EVERY]);
+        names.extend(vec!["cop"; **days / COP_EVERY]);
+    }
+    // patrols bring more of the guard out
+    names.extend(vec!["cop"; today.cops()]);
+
+    *available = AvailableCustomers(
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let handle = customers.named(name, &traits);
+                if handle.is_none() {
+                    warn!("No character named {name}");
+                }
+                handle
+            })
+            .collect(),
+    );
+}
+
+pub(super) fn tally_week(
+    mut profit: ResMut<WeekProfit>,
+    gold: Res<DailyGold>,
+    expenses: Res<DailyExpenses>,
+    ledger: Res<Ledger>,
+) {
+    **profit += **gold - **expenses - ledger.total();
+}
+
+pub(super) fn check_goal(
+    mut outcome: ResMut<GameOutcome>,
+    profit: Res<WeekProfit>,
+    day: Res<DayIndex>,
+    week: CurrentWeek,
+    mode: Res<GameMode>,
+) {
+    // endless runs only end when the stall is lost
+    let (Some(current), GameMode::Campaign) = (week.get(), *mode) else {
+        return;
+    };
+
+    if week.is_over(&day) && *outcome == GameOutcome::Survived && **profit < current.goal {
+        info!("Made {} of {} this week", **profit, current.goal);
+        *outcome = GameOutcome::Missed;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn next_week(
+    mut er: EventReader<NextWeek>,
+    mut index: ResMut<WeekIndex>,
+    mut day: ResMut<DayIndex>,
+    mut profit: ResMut<WeekProfit>,
+    mut save: ResMut<SaveData>,
+    mut state: ResMut<NextState<GameState>>,
+    mode: Res<GameMode>,
+    data: Res<GameData>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    if er.read().last().is_none() {
+        return;
+    }
+
+    // endless runs go round the same week forever
+    if *mode == GameMode::Endless {
+        **day = 0;
+        **profit = 0.0;
+        state.set(GameState::Shop);
+        return;
+    }
+
+    let weeks = campaigns
+        .get(&data.campaign)
+        .map(|c| c.weeks.len())
+        .unwrap_or_default();
+
+    if **index + 1 < weeks {
+        **index += 1;
+        **day = 0;
+        **profit = 0.0;
+        save.campaign_week = **index;
+        state.set(GameState::Shop);
+    } else {
+        // the whole campaign is done, the next one starts from the top
+        save.campaign_week = 0;
+        state.set(GameState::GameOver);
+    }
+}
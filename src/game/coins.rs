@@ -0,0 +1,453 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+use leafwing_input_manager::action_state::ActionState;
+use rand::prelude::*;
+
+use crate::{
+    assets::CharacterTraits,
+    input::Action,
+    save::SaveData,
+    ui::tooltips::{TooltipBundle, TooltipText},
+    utils::{despawn_all, text_box::SpawnTextBox},
+};
+
+use super::{
+    customer::{AttentionState, Customer, CustomerState, PriceFactor, Purse},
+    haggle::AgreedPrice,
+    prices::PriceBoard,
+    scales::{ScaleWeights, Scales, Submit},
+    till::Till,
+    townsfolk::{Attitude, Visitor},
+    ActiveDifficulty, DailyGold, GameRng, GameState, Ledger, RepLog, Reputation, TargetProduct,
+    TargetWeight,
+};
+
+/// Face values of the coins in circulation, smallest first
+pub const DENOMINATIONS: [f32; 4] = [1.0, 5.0, 10.0, 50.0];
+
+const COINS_POS: Vec3 = Vec3::new(0.3, 1.0, -0.9);
+/// Where change is laid out for the customer, in front of their coins
+const CHANGE_POS: Vec3 = Vec3::new(0.3, 1.0, -1.1);
+const COIN_SPACING: f32 = 0.12;
+const COINS_PER_ROW: usize = 6;
+const COIN_RAD: f32 = 0.04;
+pub(super) const COIN_HEIGHT: f32 = 0.01;
+/// Sums customers like to round up to when paying, so they're owed change
+const TIDY_SUMS: [f32; 3] = [1.0, 10.0, 50.0];
+/// Share of a coin's worth left once it's been clipped
+const CLIPPED_VALUE: f32 = 0.5;
+/// What a true coin weighs per gold of its face value, in grams
+const GRAMS_PER_GOLD: f32 = 0.5;
+/// Reputation gained for catching a customer paying with bad coins
+const CATCH_REP: i32 = 5;
+/// Where a coin sits on the weights' side of the beam, in the scale's own space
+const WEIGH_SPOT: Vec3 = Vec3::new(-0.5, 0.5, 0.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoinQuality {
+    Good,
+    /// Shaved down at the edges, weighs light
+    Clipped,
+    /// Not gold at all, soft when bitten
+    Fake,
+}
+
+/// A coin lying on the counter
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Coin {
+    face: f32,
+    quality: CoinQuality,
+}
+
+impl Coin {
+    /// What the coin is really worth
+    fn worth(&self) -> f32 {
+        match self.quality {
+            CoinQuality::Good => self.face,
+            CoinQuality::Clipped => self.face * CLIPPED_VALUE,
+            CoinQuality::Fake => 0.0,
+        }
+    }
+
+    fn grams(&self) -> f32 {
+        match self.quality {
+            CoinQuality::Clipped => self.face * GRAMS_PER_GOLD * CLIPPED_VALUE,
+            _ => self.face * GRAMS_PER_GOLD,
+        }
+    }
+}
+
+/// Marks coins taken out of the till to hand back as change
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Change;
+
+/// A coin put on the scale to be weighed, and where it goes back to on the counter
+#[derive(Component, Debug, Clone, Copy)]
+struct Weighing(Transform);
+
+/// What the customer owes and what they put down for it
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct Tender {
+    pub due: f32,
+    pub paid: f32,
+}
+
+/// Clicking a coin puts it in the till, or back in it when it was change.
+/// The other button bites it, or puts it on the scale while holding the modifier
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CoinClick {
+    coin: Entity,
+    button: PointerButton,
+}
+
+impl From<ListenerInput<Pointer<Down>>> for CoinClick {
+    fn from(value: ListenerInput<Pointer<Down>>) -> Self {
+        Self {
+            coin: value.target,
+            button: value.event.button,
+        }
+    }
+}
+
+/// Face value of the bad coins taken today, only noticed when the takings are counted
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut, Default)]
+pub struct BadCoins(f32);
+
+pub struct CoinsPlugin;
+
+impl Plugin for CoinsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CoinClick>()
+            .init_resource::<BadCoins>()
+            .init_resource::<Tender>()
+            .add_systems(OnEnter(CustomerState::Payment), spawn_coins)
+            .add_systems(
+                Update,
+                (inspect_coins, sort_coins, settle).run_if(in_state(CustomerState::Coins)),
+            )
+            .add_systems(OnEnter(CustomerState::End), despawn_all::<Coin>)
+            .add_systems(OnEnter(GameState::DayStart), |mut bad: ResMut<BadCoins>| {
+                **bad = 0.0
+            })
+            .add_systems(
+                OnEnter(GameState::DayEnd),
+                write_off_bad_coins.before(super::accounting),
+            );
+    }
+}
+
+/// Bigger coins for bigger denominations
+pub(super) fn coin_mesh(face: f32) -> Mesh {
+    let size = DENOMINATIONS
+        .iter()
+        .position(|d| *d == face)
+        .unwrap_or_default();
+    Mesh::from(shape::Cylinder {
+        radius: COIN_RAD * (1.0 + 0.15 * size as f32),
+        height: COIN_HEIGHT,
+        ..default()
+    })
+}
+
+/// Fewest coins that make up the amount
+fn count_out(mut amount: f32) -> Vec<f32> {
+    let mut coins = vec![];
+    for face in DENOMINATIONS.iter().rev() {
+        while amount >= *face {
+            amount -= face;
+            coins.push(*face);
+        }
+    }
+    coins
+}
+
+fn coin_pos(origin: Vec3, i: usize) -> Vec3 {
+    let (row, col) = (i / COINS_PER_ROW, i % COINS_PER_ROW);
+    origin
+        + Vec3::new(
+            COIN_SPACING * col as f32,
+            COIN_HEIGHT / 2.0,
+            COIN_SPACING * row as f32,
+        )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_coins(
+    mut cmd: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q: Query<(&Customer, Option<&Purse>)>,
+    chars: Res<Assets<CharacterTraits>>,
+    target: Res<TargetWeight>,
+    product: Res<TargetProduct>,
+    agreed: Res<AgreedPrice>,
+    board: Res<PriceBoard>,
+    factor: Res<PriceFactor>,
+    mut tender: ResMut<Tender>,
+    mut rng: ResMut<GameRng>,
+) {
+    let due = (agreed.or_list(&target, &product, &board) * **factor)
+        .round()
+        .max(1.0);
+    let purse = q
+        .iter()
+        .find_map(|(_, p)| p.map(|p| **p))
+        .unwrap_or(f32::INFINITY);
+
+    // most round up to a tidy sum and leave the counting of change to the stall
+    let tidy = TIDY_SUMS.choose(&mut **rng).copied().unwrap_or(1.0);
+    let paid = match (due / tidy).ceil() * tidy {
+        paid if paid <= purse => paid,
+        _ => due,
+    };
+    *tender = Tender { due, paid };
+
+    let faces = count_out(paid);
+    let count = faces.len();
+
+    // some customers slip a few bad coins in with the good ones
+    let counterfeit = q
+        .iter()
+        .filter_map(|(c, _)| chars.get(&c.0))
+        .any(|t| rng.gen_bool(t.counterfeit as f64));
+    let bad = if counterfeit {
+        let n = rng.gen_range(1..=(count / 2).max(1));
+        (0..count).choose_multiple(&mut **rng, n)
+    } else {
+        vec![]
+    };
+
+    for (i, face) in faces.into_iter().enumerate() {
+        let quality = match bad.contains(&i) {
+            true if rng.gen_bool(0.5) => CoinQuality::Clipped,
+            true => CoinQuality::Fake,
+            false => CoinQuality::Good,
+        };
+
+        cmd.spawn((
+            PbrBundle {
+                mesh: meshes.add(coin_mesh(face)),
+                material: materials.add(Color::GOLD.into()),
+                transform: Transform::from_translation(coin_pos(COINS_POS, i)),
+                ..default()
+            },
+            Coin { face, quality },
+            TooltipBundle::new(format!("{face:.0} gold coin")),
+            On::<Pointer<Down>>::send_event::<CoinClick>(),
+        ));
+    }
+}
+
+/// Lays a coin from the till out on the counter as change
+pub(super) fn spawn_change(
+    cmd: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    face: f32,
+    i: usize,
+) {
+    cmd.spawn((
+        PbrBundle {
+            mesh: meshes.add(coin_mesh(face)),
+            material: materials.add(Color::GOLD.into()),
+            transform: Transform::from_translation(coin_pos(CHANGE_POS, i)),
+            ..default()
+        },
+        Coin {
+            face,
+            quality: CoinQuality::Good,
+        },
+        Change,
+        TooltipBundle::new(format!("{face:.0} gold in change")),
+        On::<Pointer<Down>>::send_event::<CoinClick>(),
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn inspect_coins(
+    mut cmd: Commands,
+    mut er: EventReader<CoinClick>,
+    mut coins: Query<
+        (
+            Entity,
+            &mut Coin,
+            &mut Transform,
+            &mut TooltipText,
+            &Handle<StandardMaterial>,
+            Option<&Weighing>,
+        ),
+        Without<Change>,
+    >,
+    scales_q: Query<Entity, With<Scales>>,
+    mut scale_weights: ResMut<ScaleWeights>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    actions: Res<ActionState<Action>>,
+    mut rep: ResMut<Reputation>,
+    mut log: ResMut<RepLog>,
+    mut spawn_text: EventWriter<SpawnTextBox>,
+) {
+    let mut caught = false;
+
+    for click in er.read() {
+        if click.button != PointerButton::Secondary {
+            continue;
+        }
+        let weigh = actions.pressed(Action::Mod);
+
+        // only one coin fits on the beam, whatever was there goes back on the counter
+        if weigh {
+            for (ent, coin, mut tr, _, _, weighing) in coins.iter_mut() {
+                let Some(Weighing(home)) = weighing else {
+                    continue;
+                };
+                *tr = *home;
+                scale_weights.left -= coin.grams();
+                cmd.entity(ent).remove_parent().remove::<Weighing>();
+            }
+        }
+
+        let Ok((ent, coin, mut tr, mut tip, mat, weighing)) = coins.get_mut(click.coin) else {
+            continue;
+        };
+        // weighing it again just takes it off
+        if weigh && weighing.is_some() {
+            continue;
+        }
+
+        if weigh {
+            let Ok(scales) = scales_q.get_single() else {
+                continue;
+            };
+            // the scale is shrunk down, so the coin is blown back up to keep its size
+            cmd.entity(ent).insert(Weighing(*tr)).set_parent(scales);
+            *tr = Transform::from_translation(WEIGH_SPOT + Vec3::Y * COIN_HEIGHT * 2.0)
+                .with_scale(Vec3::splat(4.0));
+            scale_weights.left += coin.grams();
+        }
+
+        let bad = match (weigh, coin.quality) {
+            (true, CoinQuality::Clipped) => {
+                tip.0 = format!("{:.1} grams, it's been clipped!", coin.grams());
+                true
+            }
+            (true, _) => {
+                tip.0 = format!("{:.1} grams, as it should be", coin.grams());
+                false
+            }
+            (false, CoinQuality::Fake) => {
+                tip.0 = "Soft as lead, it's a fake!".to_string();
+                true
+            }
+            (false, _) => {
+                tip.0 = "Hard as gold".to_string();
+                false
+            }
+        };
+
+        if bad {
+            if let Some(mat) = materials.get_mut(mat) {
+                mat.base_color = Color::DARK_GRAY;
+            }
+            caught = true;
+        }
+    }
+
+    if !caught {
+        return;
+    }
+
+    // found out, they swap the bad coins for good ones
+    for (_, mut coin, _, mut tip, mat, weighing) in coins.iter_mut() {
+        if coin.quality == CoinQuality::Good {
+            continue;
+        }
+        let light = coin.grams();
+        coin.quality = CoinQuality::Good;
+        if weighing.is_some() {
+            scale_weights.left += coin.grams() - light;
+        }
+        tip.0 = format!("{:.0} gold coin, a real one this time", coin.face);
+        if let Some(mat) = materials.get_mut(mat) {
+            mat.base_color = Color::GOLD;
+        }
+    }
+    log.add("Caught bad coins", rep.change(CATCH_REP));
+    spawn_text.send("Ah... must have mixed up my purses. Here, real coin this time.".into());
+}
+
+/// Puts coins into the till, wherever they are on the counter
+fn sort_coins(
+    mut cmd: Commands,
+    mut er: EventReader<CoinClick>,
+    coins: Query<(&Coin, Option<&Change>, Has<Weighing>)>,
+    mut till: ResMut<Till>,
+    mut bad: ResMut<BadCoins>,
+    mut scale_weights: ResMut<ScaleWeights>,
+) {
+    for click in er.read() {
+        if click.button != PointerButton::Primary {
+            continue;
+        }
+        let Ok((coin, change, weighing)) = coins.get(click.coin) else {
+            continue;
+        };
+        if weighing {
+            scale_weights.left -= coin.grams();
+        }
+
+        if change.is_none() {
+            **bad += coin.face - coin.worth();
+        }
+        till.put(coin.face);
+        cmd.entity(click.coin).despawn_recursive();
+    }
+}
+
+/// Rings up the sale once everything they paid is in the till and the change is out
+#[allow(clippy::too_many_arguments)]
+fn settle(
+    mut er: EventReader<Submit>,
+    paid_q: Query<(), (With<Coin>, Without<Change>)>,
+    change_q: Query<&Coin, With<Change>>,
+    cust_q: Query<(&Customer, Option<&Visitor>)>,
+    chars: Res<Assets<CharacterTraits>>,
+    save: Res<SaveData>,
+    difficulty: Res<ActiveDifficulty>,
+    attention: Res<State<AttentionState>>,
+    tender: Res<Tender>,
+    mut gold: ResMut<DailyGold>,
+    mut state: ResMut<NextState<CustomerState>>,
+) {
+    for _ in er.read() {
+        if !paid_q.is_empty() {
+            continue;
+        }
+        **gold += tender.due;
+
+        let given: f32 = change_q.iter().map(|c| c.face).sum();
+        let short = tender.paid - tender.due - given;
+
+        // short changed customers count it again if they're paying attention
+        let noticed = cust_q.iter().any(|(cust, visitor)| {
+            let Some(traits) = chars.get(&cust.0) else {
+                return false;
+            };
+            let attitude = visitor.map_or(Attitude::New, |v| v.attitude(&save));
+            let threshold =
+                traits.attention_type.sus_threshold() * difficulty.threshold * attitude.threshold();
+            *attention == AttentionState::Attent && short / tender.paid > threshold
+        });
+
+        if short > 0.0 && noticed {
+            state.set(CustomerState::Angry);
+        } else {
+            state.set(CustomerState::End);
+        }
+    }
+}
+
+fn write_off_bad_coins(bad: Res<BadCoins>, mut ledger: ResMut<Ledger>) {
+    if **bad > 0.0 {
+        ledger.push(("Bad coins", **bad));
+    }
+}
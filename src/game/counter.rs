@@ -0,0 +1,156 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    ui::tooltips::{TooltipBundle, TooltipText},
+    utils::Total,
+};
+
+use super::{
+    goods::{ItemType, RemoveItem, Unit, ITEM_COLORS},
+    GameState,
+};
+
+/// Where counted goods get laid out, just past the bell
+pub const COUNTER_POS: Vec3 = Vec3::new(1.1, 1.0, -0.5);
+pub const PILE_SPACING: f32 = 0.25;
+pub const PILE_HEIGHT: f32 = 0.05;
+
+/// Goods that are sold by count and never touch the scale
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct CounterContents {
+    /// What the customer saw being put on the counter
+    pub shown: HashMap<ItemType, f32>,
+    /// What is actually going into their bag
+    pub given: HashMap<ItemType, f32>,
+}
+
+impl CounterContents {
+    pub fn put(&mut self, t: ItemType, amnt: f32) {
+        *self.shown.entry(t).or_default() += amnt;
+        *self.given.entry(t).or_default() += amnt;
+    }
+
+    /// Take goods back in plain view, returns whether there was anything to take
+    pub fn take(&mut self, t: ItemType, amnt: f32) -> bool {
+        let Some(shown) = self.shown.get_mut(&t) else {
+            return false;
+        };
+        *shown -= amnt;
+
+        if let Some(given) = self.given.get_mut(&t) {
+            *given = (*given - amnt).max(0.0);
+        }
+
+        self.shown.retain(|_, v| *v > 0.0);
+        self.given.retain(|_, v| *v > 0.0);
+        true
+    }
+
+    /// Secretly take one piece back without the customer's count changing
+    pub fn palm(&mut self, t: ItemType) -> bool {
+        let Some(given) = self.given.get_mut(&t) else {
+            return false;
+        };
+        *given -= 1.0;
+
+        self.given.retain(|_, v| *v > 0.0);
+        true
+    }
+
+    /// Put a palmed piece back on the counter
+    pub fn unpalm(&mut self, t: ItemType) {
+        *self.given.entry(t).or_default() += 1.0;
+    }
+
+    /// Percentage of the counted goods the customer is being shorted
+    pub fn shortfall(&self) -> f32 {
+        let shown = self.shown.total();
+        if shown == 0.0 {
+            return 0.0;
+        }
+
+        (shown - self.given.total()) / shown
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shown.is_empty()
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct OnCounter;
+
+pub struct CounterPlugin;
+
+impl Plugin for CounterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CounterContents>()
+            .add_systems(OnEnter(GameState::MainMenu), spawn_counter)
+            .add_systems(
+                Update,
+                counter_piles.run_if(resource_changed::<CounterContents>()),
+            );
+    }
+}
+
+pub fn reset(mut counter: ResMut<CounterContents>) {
+    *counter = CounterContents::default();
+}
+
+fn spawn_counter(
+    mut cmd: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Mesh::from(shape::Cylinder {
+        radius: 0.08,
+        height: PILE_HEIGHT,
+        ..default()
+    }));
+
+    for (i, t) in ItemType::iter()
+        .filter(|t| t.unit() == Unit::Count)
+        .enumerate()
+    {
+        cmd.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: materials.add(ITEM_COLORS[t as usize].into()),
+                transform: Transform::from_translation(
+                    COUNTER_POS + Vec3::new(PILE_SPACING * i as f32, 0.0, 0.0),
+                )
+                .with_scale(Vec3::ZERO),
+                ..default()
+            },
+            t,
+            OnCounter,
+            TooltipBundle::new(""),
+            On::<Pointer<Down>>::send_event::<RemoveItem>(),
+        ));
+    }
+}
+
+fn counter_piles(
+    mut q: Query<(&mut Transform, &mut TooltipText, &ItemType), With<OnCounter>>,
+    counter: Res<CounterContents>,
+) {
+    for (mut tr, mut txt, t) in q.iter_mut() {
+        let shown = *counter.shown.get(t).unwrap_or(&0.0);
+        let given = *counter.given.get(t).unwrap_or(&0.0);
+
+        // piles grow upwards, one layer per piece
+        tr.scale = if shown > 0.0 {
+            Vec3::new(1.0, shown, 1.0)
+        } else {
+            Vec3::ZERO
+        };
+        tr.translation.y = COUNTER_POS.y + PILE_HEIGHT * shown / 2.0;
+
+        txt.0 = if given < shown {
+            format!("{} ({} palmed)", t.describe(shown), shown - given)
+        } else {
+            t.describe(shown)
+        };
+    }
+}
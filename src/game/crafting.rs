@@ -0,0 +1,375 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    assets::{GameData, RecipeBook},
+    ui::tooltips::{TooltipBundle, TooltipText},
+    utils::{CalcCost, Total},
+};
+
+use super::{
+    counter::{OnCounter, COUNTER_POS, PILE_HEIGHT, PILE_SPACING},
+    goods::{Quality, Stock, Unit},
+    scales::{self, ScaleContents},
+    DailyExpenses, GameState, ItemType,
+};
+
+/// Where the cauldron sits, across the scale from the bell
+pub const CAULDRON_POS: Vec3 = Vec3::new(-0.75, 1.0, -0.5);
+/// Where the first shelved product sits, the rest line up away from the scale
+pub const SHELF_POS: Vec3 = Vec3::new(-1.1, 1.0, -0.5);
+
+/// Finished products, keeping how well each piece turned out
+#[derive(Debug, Clone, Default, Deref, DerefMut)]
+pub struct Products(HashMap<String, Vec<f32>>);
+
+impl Products {
+    pub fn add(&mut self, name: &str, accuracy: f32) {
+        self.entry(name.to_string()).or_default().push(accuracy);
+    }
+
+    /// Take the best piece of `name`, if there is one
+    pub fn take(&mut self, name: &str) -> Option<f32> {
+        let pieces = self.get_mut(name)?;
+        let best = pieces
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)?;
+        let accuracy = pieces.swap_remove(best);
+
+        self.retain(|_, v| !v.is_empty());
+        Some(accuracy)
+    }
+
+    pub fn count(&self, name: &str) -> usize {
+        self.get(name).map(Vec::len).unwrap_or_default()
+    }
+
+    pub fn total(&self) -> usize {
+        self.values().map(Vec::len).sum()
+    }
+
+    /// How well the worst piece turned out
+    pub fn accuracy(&self) -> f32 {
+        self.values().flatten().copied().fold(1.0, f32::min)
+    }
+
+    /// Move everything over to `other`
+    pub fn move_to(&mut self, other: &mut Products) {
+        for (name, mut pieces) in self.drain() {
+            other.entry(name).or_default().append(&mut pieces);
+        }
+    }
+}
+
+/// Products waiting on the shelf to be sold
+#[derive(Resource, Debug, Clone, Default, Deref, DerefMut)]
+pub struct Shelf(Products);
+
+/// Products put out on the counter for the current customer
+#[derive(Resource, Debug, Clone, Default, Deref, DerefMut)]
+pub struct Handed(Products);
+
+/// A batch of products in the cauldron
+#[derive(Resource, Debug, Clone)]
+pub struct Brewing {
+    pub recipe: String,
+    pub count: u32,
+    pub accuracy: f32,
+    pub timer: Timer,
+}
+
+#[derive(Component, Debug)]
+pub struct Cauldron;
+
+/// Marks the jar or counter pile of a product, by recipe name
+#[derive(Component, Debug, Clone, Deref)]
+pub struct Product(String);
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Craft;
+
+impl From<ListenerInput<Pointer<Down>>> for Craft {
+    fn from(_: ListenerInput<Pointer<Down>>) -> Self {
+        Self
+    }
+}
+
+/// Put a product from the shelf out on the counter
+#[derive(Event, Deref)]
+pub struct HandOver(Entity);
+
+impl From<ListenerInput<Pointer<Down>>> for HandOver {
+    fn from(value: ListenerInput<Pointer<Down>>) -> Self {
+        Self(value.target)
+    }
+}
+
+/// Put a product from the counter back on the shelf
+#[derive(Event, Deref)]
+pub struct TakeBack(Entity);
+
+impl From<ListenerInput<Pointer<Down>>> for TakeBack {
+    fn from(value: ListenerInput<Pointer<Down>>) -> Self {
+        Self(value.target)
+    }
+}
+
+pub struct CraftingPlugin;
+
+impl Plugin for CraftingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Craft>()
+            .add_event::<HandOver>()
+            .add_event::<TakeBack>()
+            .init_resource::<Shelf>()
+            .init_resource::<Handed>()
+            .add_systems(OnEnter(GameState::MainMenu), spawn_workshop)
+            .add_systems(OnEnter(GameState::Reset), reset)
+            .add_systems(
+                Update,
+                (
+                    start_brewing,
+                    apply_deferred,
+                    scales::reset.run_if(resource_added::<Brewing>()),
+                )
+                    .chain()
+                    // brewing clears the scale, so not while a customer's order is on it
+                    .run_if(in_state(GameState::Waiting)),
+            )
+            .add_systems(
+                Update,
+                (
+                    brew.run_if(resource_exists::<Brewing>().and_then(
+                        in_state(GameState::Waiting).or_else(in_state(GameState::Customer)),
+                    )),
+                    show_brewing,
+                    handle_hand_over,
+                    handle_take_back,
+                    show_products
+                        .run_if(resource_changed::<Shelf>().or_else(resource_changed::<Handed>())),
+                ),
+            );
+    }
+}
+
+fn reset(mut cmd: Commands, mut shelf: ResMut<Shelf>, mut handed: ResMut<Handed>) {
+    *shelf = Shelf::default();
+    *handed = Handed::default();
+    cmd.remove_resource::<Brewing>();
+}
+
+/// Put anything the customer didn't take back on the shelf
+pub fn return_handed(mut shelf: ResMut<Shelf>, mut handed: ResMut<Handed>) {
+    handed.move_to(&mut shelf);
+}
+
+fn spawn_workshop(
+    mut cmd: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    data: Res<GameData>,
+    books: Res<Assets<RecipeBook>>,
+) {
+    cmd.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Cylinder {
+                radius: 0.15,
+                height: 0.2,
+                ..default()
+            })),
+            material: materials.add(Color::DARK_GRAY.into()),
+            transform: Transform::from_translation(CAULDRON_POS),
+            ..default()
+        },
+        Cauldron,
+        TooltipBundle::new("Cauldron"),
+        On::<Pointer<Down>>::send_event::<Craft>(),
+    ));
+
+    let Some(book) = books.get(&data.recipes) else {
+        error!("Recipe book asset was missing");
+        return;
+    };
+
+    let jar = meshes.add(Mesh::from(shape::Cylinder {
+        radius: 0.06,
+        height: 0.15,
+        ..default()
+    }));
+    let pile = meshes.add(Mesh::from(shape::Cylinder {
+        radius: 0.06,
+        height: PILE_HEIGHT,
+        ..default()
+    }));
+    let material = materials.add(Color::TEAL.into());
+    // products go on the counter after the counted goods
+    let counted = ItemType::iter().filter(|t| t.unit() == Unit::Count).count();
+
+    for (i, recipe) in book.recipes.iter().enumerate() {
+        cmd.spawn((
+            PbrBundle {
+                mesh: jar.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(
+                    SHELF_POS - Vec3::new(PILE_SPACING * i as f32, 0.0, 0.0),
+                ),
+                ..default()
+            },
+            Product(recipe.name.clone()),
+            TooltipBundle::new(format!("{} (none left)", recipe.name)),
+            On::<Pointer<Down>>::send_event::<HandOver>(),
+        ));
+
+        cmd.spawn((
+            PbrBundle {
+                mesh: pile.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(
+                    COUNTER_POS + Vec3::new(PILE_SPACING * (counted + i) as f32, 0.0, 0.0),
+                )
+                .with_scale(Vec3::ZERO),
+                ..default()
+            },
+            Product(recipe.name.clone()),
+            OnCounter,
+            TooltipBundle::new(""),
+            On::<Pointer<Down>>::send_event::<TakeBack>(),
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn start_brewing(
+    mut cmd: Commands,
+    mut er: EventReader<Craft>,
+    brewing: Option<Res<Brewing>>,
+    contents: Res<ScaleContents>,
+    mut stock: ResMut<Stock>,
+    mut expenses: ResMut<DailyExpenses>,
+    data: Res<GameData>,
+    books: Res<Assets<RecipeBook>>,
+) {
+    for _event in er.read() {
+        // one batch at a time
+        if brewing.is_some() {
+            continue;
+        }
+
+        let Some(book) = books.get(&data.recipes) else {
+            error!("Recipe book asset was missing");
+            return;
+        };
+        let Some(recipe) = book.matching(&contents) else {
+            continue;
+        };
+
+        let count = (contents.total() / recipe.weight()).floor() as u32;
+        if count == 0 {
+            continue;
+        }
+
+        // everything on the scale goes in, extra grams included
+        **expenses += contents.cost();
+        stock.take(&contents);
+
+        cmd.insert_resource(Brewing {
+            recipe: recipe.name.clone(),
+            count,
+            accuracy: recipe.accuracy(&contents),
+            timer: Timer::from_seconds(recipe.time * count as f32, TimerMode::Once),
+        });
+        break;
+    }
+}
+
+fn brew(
+    mut cmd: Commands,
+    mut brewing: ResMut<Brewing>,
+    mut shelf: ResMut<Shelf>,
+    time: Res<Time>,
+) {
+    if brewing.timer.tick(time.delta()).finished() {
+        for _ in 0..brewing.count {
+            shelf.add(&brewing.recipe, brewing.accuracy);
+        }
+        cmd.remove_resource::<Brewing>();
+    }
+}
+
+fn show_brewing(mut q: Query<&mut TooltipText, With<Cauldron>>, brewing: Option<Res<Brewing>>) {
+    for mut txt in q.iter_mut() {
+        txt.0 = match &brewing {
+            Some(b) => format!(
+                "Brewing {} {} ({:.0}s)",
+                b.count,
+                b.recipe,
+                b.timer.remaining_secs()
+            ),
+            None => "Cauldron".to_string(),
+        };
+    }
+}
+
+fn handle_hand_over(
+    mut er: EventReader<HandOver>,
+    q: Query<&Product, Without<OnCounter>>,
+    mut shelf: ResMut<Shelf>,
+    mut handed: ResMut<Handed>,
+) {
+    for event in er.read() {
+        let Ok(product) = q.get(**event) else {
+            continue;
+        };
+        if let Some(accuracy) = shelf.take(product) {
+            handed.add(product, accuracy);
+        }
+    }
+}
+
+fn handle_take_back(
+    mut er: EventReader<TakeBack>,
+    q: Query<&Product, With<OnCounter>>,
+    mut shelf: ResMut<Shelf>,
+    mut handed: ResMut<Handed>,
+) {
+    for event in er.read() {
+        let Ok(product) = q.get(**event) else {
+            continue;
+        };
+        if let Some(accuracy) = handed.take(product) {
+            shelf.add(product, accuracy);
+        }
+    }
+}
+
+fn show_products(
+    mut jars: Query<(&Product, &mut TooltipText), Without<OnCounter>>,
+    mut piles: Query<(&Product, &mut Transform, &mut TooltipText), With<OnCounter>>,
+    shelf: Res<Shelf>,
+    handed: Res<Handed>,
+) {
+    for (product, mut txt) in jars.iter_mut() {
+        let count = shelf.count(product);
+        txt.0 = match shelf.get(&**product) {
+            Some(pieces) => {
+                let best = pieces.iter().copied().fold(0.0, f32::max);
+                format!("{} x{count} ({})", **product, Quality::grade(best))
+            }
+            None => format!("{} (none left)", **product),
+        };
+    }
+
+    for (product, mut tr, mut txt) in piles.iter_mut() {
+        let count = handed.count(product) as f32;
+
+        tr.scale = if count > 0.0 {
+            Vec3::new(1.0, count, 1.0)
+        } else {
+            Vec3::ZERO
+        };
+        tr.translation.y = COUNTER_POS.y + PILE_HEIGHT * count / 2.0;
+        txt.0 = format!("{} x{count}", **product);
+    }
+}
@@ -9,18 +9,29 @@ use bevy_tweening::{
 use rand::prelude::*;
 
 use crate::{
-    assets::CharacterTraits,
-    crowd::ROAD_OFFSET,
+    assets::{CharacterTraits, GameData, RecipeBook},
+    crowd::{CrowdMember, ROAD_OFFSET},
+    save::SaveData,
     utils::{
         despawn_all,
         text_box::{SpawnTextBox, TextBox},
-        CalcCost, IntoAnimator, PercentDiff, Ratios, Total, TweenDone,
+        CalcCost, IntoAnimator, PercentDiff, Ratios, Total, TweenDone, Units,
     },
 };
 
 use super::{
+    coins::Tender,
+    counter::{self, CounterContents},
+    crafting::{self, Handed},
+    goods::{Quality, Stock},
+    offenses::Caught,
+    prices::PriceBoard,
     scales::{self, ScaleContents, ScaleIsSus, ScaleWeights, Submit, SusEvent},
-    Advance, AvailableCustomers, DailyExpenses, DailyGold, GameState, Reputation, TargetWeight,
+    thieves::Thief,
+    townsfolk::{self, Attitude, Visitor},
+    upgrades::Perks,
+    ActiveDifficulty, Advance, AvailableCustomers, DailyExpenses, GameRng, GameState, ItemRequest,
+    RepLog, Reputation, TargetProduct, TargetWeight, Weather,
 };
 
 #[allow(dead_code)]
@@ -29,11 +40,18 @@ pub enum CustomerState {
     Approach,
     Greeting,
     Request,
+    /// Settling on a price before anything is measured out
+    Haggle,
     Measuring,
     Review,
     Reject,
     Angry,
+    Refuse,
+    /// Pushed too far while haggling
+    Walkout,
     Payment,
+    /// Coins are on the counter, waiting to be checked and taken
+    Coins,
     #[default]
     End,
 }
@@ -62,10 +80,27 @@ pub struct WillChange;
 pub struct CustomerEyes;
 
 #[derive(Component)]
-pub struct Customer(Handle<CharacterTraits>);
+pub struct Customer(pub(super) Handle<CharacterTraits>);
+
+/// Gold the customer has on them to spend
+#[derive(Component, Debug, Clone, Copy, Deref)]
+pub struct Purse(f32);
+
+/// How much of the usual price the customer is willing to pay for this order
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut)]
+pub struct PriceFactor(f32);
+
+impl Default for PriceFactor {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
 
 pub const CUSTOMER_STAND_POINT: Vec3 = Vec3::new(0.0, 0.0, -3.0);
 const SHOP_TURN_POINT: Transform = Transform::from_xyz(CUSTOMER_STAND_POINT.x, 1.0, ROAD_OFFSET);
+/// How quickly customers walk up to the stall, in units per second
+const WALK_SPEED: f32 = 10.0;
+const MIN_WALK_SECS: f32 = 0.3;
 
 pub struct CustomerPlugin;
 
@@ -74,6 +109,8 @@ impl Plugin for CustomerPlugin {
         app.add_state::<CustomerState>()
             .add_state::<AttentionState>()
             .init_resource::<TargetWeight>()
+            .init_resource::<TargetProduct>()
+            .init_resource::<PriceFactor>()
             .add_systems(
                 Update,
                 (
@@ -96,7 +133,13 @@ impl Plugin for CustomerPlugin {
             )
             .add_systems(
                 OnEnter(CustomerState::End),
-                (walk_out, cleanup, scales::reset),
+                (
+                    walk_out,
+                    cleanup,
+                    scales::reset,
+                    counter::reset,
+                    crafting::return_handed,
+                ),
             )
             .add_systems(
                 OnExit(CustomerState::Measuring),
@@ -180,7 +223,7 @@ fn animate_distraction(
     mut look_left: Local<bool>,
 ) {
     if attention.is_changed() && *attention == AttentionState::Attent {
-        *look_left = SmallRng::from_entropy().gen();
+        *look_left = thread_rng().gen();
     }
 
     for (ent, tr) in changed.iter() {
@@ -227,6 +270,7 @@ fn animate_distraction(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_customer(
     mut cmd: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -234,23 +278,51 @@ fn spawn_customer(
     mut state: ResMut<NextState<CustomerState>>,
     chars: Res<Assets<CharacterTraits>>,
     available: Res<AvailableCustomers>,
+    crowd_q: Query<(Entity, &Transform, &CrowdMember), Without<Thief>>,
+    mut save: ResMut<SaveData>,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut rng = SmallRng::from_entropy();
-    let char = available
-        .choose(&mut rng)
-        .expect("No available customer types")
-        .clone();
-    let color = chars
-        .get(char.clone())
-        .map(|v| v.color)
-        .unwrap_or(Color::rgb(1.0, 0.0, 1.0));
-
-    let customer_spawn = Transform::from_xyz(-10.0, 1.0, ROAD_OFFSET - 2.0)
-        .with_rotation(Quat::from_rotation_y(90.0f32.to_radians()));
+    // whoever is out shopping and closest to the stall decides to stop
+    let passing = crowd_q
+        .iter()
+        .filter_map(|(ent, tr, member)| member.0.clone().map(|h| (ent, tr.translation, h)))
+        .min_by(|a, b| {
+            let dist = |p: Vec3| (p.x - SHOP_TURN_POINT.translation.x).abs();
+            dist(a.1).total_cmp(&dist(b.1))
+        });
+    let (start, char) = match passing {
+        Some((ent, pos, char)) => {
+            cmd.entity(ent).despawn_recursive();
+            (pos, char)
+        }
+        // an empty road, they come from further off
+        None => (
+            Vec3::new(-10.0, 1.0, ROAD_OFFSET - 2.0),
+            available
+                .choose(&mut **rng)
+                .expect("No available customer types")
+                .clone(),
+        ),
+    };
+    let traits = chars.get(char.clone());
+    let color = traits.map(|v| v.color).unwrap_or(Color::rgb(1.0, 0.0, 1.0));
+    let archetype = traits.map(|v| v.name.as_str()).unwrap_or_default();
+    let purse = traits.map_or(0.0, |v| rng.gen_range(v.purse.0..=v.purse.1));
+    let visitor = townsfolk::visitor(&mut save, archetype, &mut **rng);
+
+    // facing the way they were walking
+    let facing = if start.x < SHOP_TURN_POINT.translation.x {
+        90.0f32.to_radians()
+    } else {
+        -90.0f32.to_radians()
+    };
+    let customer_spawn =
+        Transform::from_translation(start).with_rotation(Quat::from_rotation_y(facing));
+    let walk_secs = start.distance(SHOP_TURN_POINT.translation) / WALK_SPEED;
 
     let walk_in = Tween::new(
         EaseFunction::QuadraticOut,
-        Duration::from_millis(1000),
+        Duration::from_secs_f32(walk_secs.max(MIN_WALK_SECS)),
         TransformPositionLens {
             start: customer_spawn.translation,
             end: SHOP_TURN_POINT.translation,
@@ -260,7 +332,7 @@ fn spawn_customer(
         EaseMethod::Linear,
         Duration::from_millis(100),
         TransformRotateYLens {
-            start: customer_spawn.rotation.y,
+            start: facing,
             end: 0.0,
         },
     ))
@@ -276,7 +348,7 @@ fn spawn_customer(
         .with_completed_event(191),
     );
 
-    cmd.spawn((
+    let mut customer = cmd.spawn((
         PbrBundle {
             mesh: meshes.add(Mesh::from(shape::Capsule {
                 radius: 1.0,
@@ -292,8 +364,13 @@ fn spawn_customer(
         }),
         Animator::new(walk_in),
         Customer(char),
-    ))
-    .with_children(|parent| {
+        Purse(purse),
+    ));
+    // strangers passing through aren't on the roster
+    if let Some(visitor) = visitor {
+        customer.insert(visitor);
+    }
+    customer.with_children(|parent| {
         parent
             .spawn((
                 SpatialBundle {
@@ -340,18 +417,24 @@ fn spawn_customer(
     state.set(CustomerState::Approach);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn get_distracted(
     mut cmd: Commands,
-    q: Query<(Entity, &Customer)>,
+    q: Query<(Entity, &Customer, Option<&Visitor>)>,
     chars: Res<Assets<CharacterTraits>>,
+    save: Res<SaveData>,
     time: Res<Time>,
     current_state: Res<State<AttentionState>>,
     mut state: ResMut<NextState<AttentionState>>,
+    perks: Res<Perks>,
+    difficulty: Res<ActiveDifficulty>,
+    weather: Res<Weather>,
+    mut rng: ResMut<GameRng>,
     mut will_change: Local<bool>,
     mut delay: Local<Timer>,
     mut lockout: Local<Timer>,
 ) {
-    for (ent, cust) in q.iter() {
+    for (ent, cust, visitor) in q.iter() {
         // ensure a minimum amount of time spent in a state
         // kind of debouncing I guess
         if !lockout.finished() {
@@ -359,12 +442,21 @@ fn get_distracted(
             continue;
         }
         if !*will_change {
-            let mut rng = SmallRng::from_entropy();
             let Some(traits) = chars.get(&cust.0) else {
                 continue;
             };
             let weights = traits.attention_type.weights()[*current_state.get() as usize];
-            if rng.gen_ratio(weights.0, weights.1) {
+            let mut odds = weights.0 as f64 / weights.1 as f64;
+            // gadgets only help with getting distracted, not with snapping out of it
+            if *current_state.get() == AttentionState::Attent {
+                let attitude = visitor.map_or(Attitude::New, |v| v.attitude(&save));
+                let scale = perks.distraction
+                    * difficulty.distraction
+                    * attitude.distraction()
+                    * weather.distraction();
+                odds = (odds * scale as f64).min(1.0);
+            }
+            if rng.gen_bool(odds) {
                 // add a component before actually changing to enable giving some kind of
                 // visual cue to the player
                 *will_change = true;
@@ -389,19 +481,35 @@ fn handle_attention(
     }
 }
 
-fn cleanup(mut tw: ResMut<TargetWeight>) {
+fn cleanup(
+    mut tw: ResMut<TargetWeight>,
+    mut product: ResMut<TargetProduct>,
+    mut factor: ResMut<PriceFactor>,
+) {
     *tw = TargetWeight::default();
+    *product = TargetProduct::default();
+    *factor = PriceFactor::default();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn show_text(
-    cust_q: Query<&Customer>,
+    cust_q: Query<(&Customer, Option<&Visitor>, Option<&Purse>)>,
+    save: Res<SaveData>,
     state: Res<State<CustomerState>>,
     chars: Res<Assets<CharacterTraits>>,
     mut target: ResMut<TargetWeight>,
+    mut product: ResMut<TargetProduct>,
+    factor: Res<PriceFactor>,
+    tender: Res<Tender>,
+    board: Res<PriceBoard>,
+    data: Res<GameData>,
+    books: Res<Assets<RecipeBook>>,
     mut spawn_text: EventWriter<SpawnTextBox>,
+    mut next_state: ResMut<NextState<CustomerState>>,
+    mut rng: ResMut<GameRng>,
 ) {
     // .get_single wasn't working consistently here
-    for char in cust_q.iter() {
+    for (char, visitor, purse) in cust_q.iter() {
         let Some(ty) = chars.get(&char.0) else {
             error!("Character traits asset was missing");
             return;
@@ -409,25 +517,77 @@ fn show_text(
 
         match **state {
             CustomerState::Greeting => {
-                spawn_text.send(SpawnTextBox(ty.greeting.clone()));
+                let greeting = match visitor {
+                    Some(v) => v.greeting(&save, &ty.greeting),
+                    None => ty.greeting.clone(),
+                };
+                spawn_text.send(SpawnTextBox(greeting));
             }
             CustomerState::Request => {
-                let mut rng = SmallRng::from_entropy();
-                let req = ty.request.choose(&mut rng).expect("No item requests");
-                let req_text = format!("{} please", req);
+                let book = books.get(&data.recipes);
+                let price = |name: &str| {
+                    book.and_then(|b| b.get(name))
+                        .map(|r| r.price)
+                        .unwrap_or_default()
+                };
+                // customers ask for what's going cheap more often
+                let Ok(wanted) = ty.request.choose_weighted(&mut **rng, |r| r.demand(&board))
+                else {
+                    warn!("{} has nothing to ask for", ty.name);
+                    next_state.set(CustomerState::End);
+                    continue;
+                };
+                let budget = purse.map_or(f32::INFINITY, |p| **p);
+
+                // they leave with nothing when they can't afford any of it
+                let Some(req) = wanted.within(budget, &board, price) else {
+                    spawn_text.send(ty.broke.clone().into());
+                    continue;
+                };
+                let req_text = if req.cost(&board, price) < wanted.cost(&board, price) {
+                    format!("{req} please, that's all I can afford")
+                } else {
+                    format!("{req} please")
+                };
                 spawn_text.send(req_text.into());
-                *target = TargetWeight::from(req);
+                *target = TargetWeight::from_request(&req, &board);
+
+                if let ItemRequest::Product(name, count) = &req {
+                    *product = TargetProduct {
+                        name: name.clone(),
+                        count: *count,
+                        price: price(name),
+                    };
+                }
             }
             CustomerState::Review => {
                 spawn_text.send(ty.thinking.clone().into());
             }
+            CustomerState::Walkout => {
+                spawn_text.send(ty.walkout.clone().into());
+            }
             CustomerState::Payment => {
-                spawn_text
-                    .send(format!("{} Here's {} gold", ty.accept, target.customer_cost()).into());
+                let line = if **factor < 1.0 {
+                    &ty.stale
+                } else {
+                    &ty.accept
+                };
+                let text = if tender.paid > tender.due {
+                    format!(
+                        "{line} That's {:.0} gold, here's {:.0}",
+                        tender.due, tender.paid
+                    )
+                } else {
+                    format!("{line} Here's {:.0} gold", tender.due)
+                };
+                spawn_text.send(text.into());
             }
             CustomerState::Reject => {
                 spawn_text.send(ty.reject.clone().into());
             }
+            CustomerState::Refuse => {
+                spawn_text.send(ty.spoiled.clone().into());
+            }
             CustomerState::Angry => spawn_text.send(ty.accuse.clone().into()),
             _ => {}
         }
@@ -439,19 +599,39 @@ fn wait_to_advance(
     current_state: Res<State<CustomerState>>,
     mut state: ResMut<NextState<CustomerState>>,
     mut er: EventReader<Advance>,
+    visitor_q: Query<&Visitor>,
+    save: Res<SaveData>,
+    target: Res<TargetWeight>,
+    product: Res<TargetProduct>,
 ) {
     for _event in er.read() {
         match **current_state {
             CustomerState::Greeting => {
-                state.set(CustomerState::Request);
+                // some people only come by to say they won't be buying
+                let refusing = visitor_q
+                    .iter()
+                    .any(|v| v.attitude(&save) == Attitude::Refusing);
+                if refusing {
+                    state.set(CustomerState::End);
+                } else {
+                    state.set(CustomerState::Request);
+                }
             }
             CustomerState::Request => {
-                state.set(CustomerState::Measuring);
+                // nothing was asked for, so there's nothing to measure out
+                if target.is_empty() && product.count == 0 {
+                    state.set(CustomerState::End);
+                } else {
+                    state.set(CustomerState::Haggle);
+                }
             }
             CustomerState::Measuring => {
                 state.set(CustomerState::Review);
             }
-            CustomerState::Payment | CustomerState::Angry => {
+            CustomerState::Payment => {
+                state.set(CustomerState::Coins);
+            }
+            CustomerState::Angry | CustomerState::Refuse | CustomerState::Walkout => {
                 state.set(CustomerState::End);
             }
             CustomerState::Reject => {
@@ -462,71 +642,124 @@ fn wait_to_advance(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn pay(
     mut rep: ResMut<Reputation>,
     cust_q: Query<&Customer>,
     chars: Res<Assets<CharacterTraits>>,
-    mut gold: ResMut<DailyGold>,
     mut expenses: ResMut<DailyExpenses>,
-    target: Res<TargetWeight>,
+    mut stock: ResMut<Stock>,
     contents: Res<ScaleContents>,
+    counter: Res<CounterContents>,
+    mut handed: ResMut<Handed>,
+    mut log: ResMut<RepLog>,
+    difficulty: Res<ActiveDifficulty>,
 ) {
-    **gold += target.customer_cost();
-    **expenses += contents.cost();
+    // ingredients were paid for when the products were brewed
+    handed.clear();
+    **expenses += contents.cost() + counter.given.cost();
+    stock.take(&contents);
+    stock.take(&counter.given);
     for cust in cust_q.iter() {
         if let Some(t) = chars.get(&cust.0) {
-            **rep = (**rep + t.rep_hit).clamp(0, 100);
+            log.add(
+                "Customers served",
+                rep.change(difficulty.rep_hit(t.rep_hit) as i32),
+            );
         }
     }
 }
 
 fn angery(
     mut rep: ResMut<Reputation>,
+    mut log: ResMut<RepLog>,
+    mut caught: EventWriter<Caught>,
     cust_q: Query<&Customer>,
     chars: Res<Assets<CharacterTraits>>,
+    difficulty: Res<ActiveDifficulty>,
 ) {
     for cust in cust_q.iter() {
         if let Some(t) = chars.get(&cust.0) {
-            **rep = rep.saturating_sub(t.rep_hit);
+            log.add(
+                "Caught cheating",
+                rep.change(-(difficulty.rep_hit(t.rep_hit) as i32)),
+            );
         }
+        caught.send(Caught(cust.0.clone()));
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_review(
     is_sus: Option<Res<ScaleIsSus>>,
     scale_weights: Res<ScaleWeights>,
     contents: Res<ScaleContents>,
+    counter: Res<CounterContents>,
+    handed: Res<Handed>,
     target: Res<TargetWeight>,
-    q: Query<&Customer>,
+    product: Res<TargetProduct>,
+    stock: Res<Stock>,
+    mut factor: ResMut<PriceFactor>,
+    q: Query<(&Customer, Option<&Visitor>)>,
     chars: Res<Assets<CharacterTraits>>,
+    save: Res<SaveData>,
+    difficulty: Res<ActiveDifficulty>,
     mut timer: Local<Timer>,
-    time: Res<Time>,
+    // bundled together to stay within the parameter limit for systems
+    (time, mut rng): (Res<Time>, ResMut<GameRng>),
     mut state: ResMut<NextState<CustomerState>>,
 ) {
     if !timer.finished() {
         timer.tick(time.delta());
 
         if timer.just_finished() {
-            info!("{contents:?} vs {target:?}");
-            info!("Diff: {}", target.diff(&**contents));
-            let cust = q.get_single().expect("No customer?");
+            let weighed = target.weighed();
+            let counted = target.counted();
+            info!("{contents:?} + {counter:?} vs {target:?}");
+            let (cust, visitor) = q.get_single().expect("No customer?");
             let traits = chars.get(&cust.0).expect("Unable to get traits");
+            let attitude = visitor.map_or(Attitude::New, |v| v.attitude(&save));
+            let threshold =
+                traits.attention_type.sus_threshold() * difficulty.threshold * attitude.threshold();
 
             // customers can tell when the amount isn't correct
-            if traits.attention_type.sus_threshold() < target.diff(&**contents)
-            // don't let customers be fooled without using the sus weights
-                || ((target.total() != contents.total()) && is_sus.is_none())
-            {
+            let weight_off = !weighed.is_empty()
+                && (threshold < weighed.diff(&**contents)
+                // don't let customers be fooled without using the sus weights
+                    || ((weighed.total() != contents.total()) && is_sus.is_none()));
+            // or when they recount what ended up in their bag
+            let count_off = threshold < counter.shortfall();
+
+            if weight_off || count_off {
                 state.set(CustomerState::Angry);
-            } else if scale_weights.is_even() && target.ratio() == contents.ratio() {
-                state.set(CustomerState::Payment);
+            } else if scale_weights.is_even()
+                && weighed.ratio() == contents.ratio()
+                && counter.shown == counted
+                && handed.count(&product.name) == product.count as usize
+                && handed.total() == product.count as usize
+            {
+                let sold = contents.keys().chain(counter.given.keys());
+                // badly brewed products are as disappointing as old goods
+                let score = stock.freshness(sold.clone()).min(handed.accuracy());
+                let quality = stock
+                    .quality_of(sold)
+                    .max(Quality::grade(handed.accuracy()));
+                // the more attentive the customer, the sooner they notice
+                if 1.0 - score <= traits.attention_type.stale_threshold() {
+                    state.set(CustomerState::Payment);
+                } else if quality == Quality::Spoiled {
+                    state.set(CustomerState::Refuse);
+                } else {
+                    **factor = score;
+                    state.set(CustomerState::Payment);
+                }
             } else {
                 state.set(CustomerState::Reject);
             }
         }
     } else {
         *timer = Timer::new(
-            Duration::from_secs_f32(thread_rng().gen_range(1.0..=3.0)),
+            Duration::from_secs_f32(rng.gen_range(1.0..=3.0)),
             TimerMode::Once,
         );
     }
@@ -537,9 +770,13 @@ fn handle_submit(
     mut ew: EventWriter<Advance>,
     state: Res<State<CustomerState>>,
     contents: Res<ScaleContents>,
+    counter: Res<CounterContents>,
+    handed: Res<Handed>,
 ) {
     for _event in er.read() {
-        if CustomerState::Measuring == **state && contents.total() > 0.0 {
+        if CustomerState::Measuring == **state
+            && (contents.total() > 0.0 || !counter.is_empty() || handed.total() > 0)
+        {
             ew.send_default();
         }
     }
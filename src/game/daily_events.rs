@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::assets::{DailyEvent, DailyEvents, EventEffect, GameData};
+
+use super::{
+    goods::Stock, prices::PriceBoard, GameOutcome, GameRng, GameState, ItemType, Turnout, Unit,
+    Weather,
+};
+
+const QUIET_HEADLINE: &str = "All quiet at the market";
+const QUIET_STORY: &str = "Nothing much to report. Another ordinary day of trade.";
+
+/// Whatever is going on in town today
+#[derive(Resource, Debug, Clone, Default, Deref)]
+pub struct TodaysEvent(Option<DailyEvent>);
+
+impl TodaysEvent {
+    /// Cops added to the usual crowd
+    pub fn cops(&self) -> usize {
+        self.iter()
+            .flat_map(|e| e.effects.iter())
+            .map(|effect| match effect {
+                EventEffect::Cops(n) => *n,
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+/// The morning paper, the day waits until it's been read
+#[derive(Resource, Debug, Clone)]
+pub struct Announcement {
+    pub headline: String,
+    pub story: String,
+    pub weather: Weather,
+}
+
+pub struct DailyEventsPlugin;
+
+impl Plugin for DailyEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TodaysEvent>().add_systems(
+            OnEnter(GameState::Reset),
+            |mut cmd: Commands, mut today: ResMut<TodaysEvent>| {
+                *today = TodaysEvent::default();
+                cmd.remove_resource::<Announcement>();
+            },
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn roll_event(
+    mut cmd: Commands,
+    mut rng: ResMut<GameRng>,
+    mut today: ResMut<TodaysEvent>,
+    mut turnout: ResMut<Turnout>,
+    mut stock: ResMut<Stock>,
+    mut board: ResMut<PriceBoard>,
+    data: Res<GameData>,
+    events: Res<Assets<DailyEvents>>,
+    outcome: Res<GameOutcome>,
+    weather: Res<Weather>,
+) {
+    // yesterday's news is over with
+    board.restock();
+    *today = TodaysEvent::default();
+
+    let Some(events) = events.get(&data.events) else {
+        error!("Daily events asset was missing");
+        return;
+    };
+    if *outcome != GameOutcome::Survived {
+        return;
+    }
+
+    // nothing gets soaked unless it's actually raining
+    let fitting = events.events.iter().filter(|e| {
+        *weather == Weather::Rain || !e.effects.iter().any(|f| matches!(f, EventEffect::Soak(_)))
+    });
+    let options: Vec<_> = std::iter::once((None, events.quiet))
+        .chain(fitting.map(|e| (Some(e), e.weight)))
+        .collect();
+    let Ok((Some(event), _)) = options.choose_weighted(&mut **rng, |(_, w)| *w) else {
+        // still worth a read for the weather
+        cmd.insert_resource(Announcement {
+            headline: QUIET_HEADLINE.to_string(),
+            story: QUIET_STORY.to_string(),
+            weather: *weather,
+        });
+        return;
+    };
+    let event = (*event).clone();
+
+    let mut good = None;
+    for effect in event.effects.iter() {
+        match *effect {
+            EventEffect::Turnout(factor) => **turnout *= factor,
+            EventEffect::Soak(loss) => {
+                for (t, batch) in stock.iter_mut() {
+                    if t.unit() == Unit::Grams {
+                        batch.freshness = (batch.freshness - loss).max(0.0);
+                    }
+                }
+            }
+            // picked up by the campaign when it works out who's about
+            EventEffect::Cops(_) => {}
+            EventEffect::Shortage(factor) => {
+                let t = ItemType::iter().choose(&mut **rng).expect("No goods");
+                board.shortage(t, factor);
+                good = Some(t);
+            }
+        }
+    }
+
+    info!("Today's event: {}", event.name);
+    let headline = match good {
+        Some(t) => event.headline.replace("{good}", t.label()),
+        None => event.headline.clone(),
+    };
+    cmd.insert_resource(Announcement {
+        headline,
+        story: event.story.clone(),
+        weather: *weather,
+    });
+    *today = TodaysEvent(Some(event));
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::save::{HighScore, SaveData};
+
+use super::{ActiveDifficulty, DayTimer, GameState, TotalExpenses, TotalGold};
+
+/// A day gets this many seconds shorter for every day survived
+const DAY_SHRINK: f32 = 2.0;
+/// Days never get shorter than this
+const MIN_DAY_LEN: f32 = 30.0;
+/// Days survived between each extra attentive customer in the crowd
+pub const ATTENTIVE_EVERY: usize = 2;
+/// Days survived between each extra cop in the crowd
+pub const COP_EVERY: usize = 3;
+/// Score for every day survived, on top of the profit made
+const SCORE_PER_DAY: f32 = 50.0;
+
+/// Whether the game is the campaign or an endless run
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Campaign,
+    /// Days keep coming until the stall is lost
+    Endless,
+}
+
+/// Days finished over the whole game
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut, Default)]
+pub struct DaysSurvived(pub(super) usize);
+
+pub fn score(gold: &TotalGold, expenses: &TotalExpenses, days: &DaysSurvived) -> f32 {
+    **gold - **expenses + **days as f32 * SCORE_PER_DAY
+}
+
+pub fn is_endless(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::Endless
+}
+
+pub struct EndlessPlugin;
+
+impl Plugin for EndlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>()
+            .init_resource::<DaysSurvived>()
+            .add_systems(
+                OnEnter(GameState::DayStart),
+                set_day_len.after(super::start_day),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                record_score.after(super::accounting).run_if(is_endless),
+            );
+    }
+}
+
+fn set_day_len(
+    mut timer: ResMut<DayTimer>,
+    mode: Res<GameMode>,
+    days: Res<DaysSurvived>,
+    difficulty: Res<ActiveDifficulty>,
+) {
+    let len = match *mode {
+        GameMode::Campaign => difficulty.day_len,
+        GameMode::Endless => (difficulty.day_len - **days as f32 * DAY_SHRINK)
+            .max(MIN_DAY_LEN.min(difficulty.day_len)),
+    };
+    timer.set_duration(Duration::from_secs_f32(len));
+}
+
+fn record_score(
+    mut save: ResMut<SaveData>,
+    gold: Res<TotalGold>,
+    expenses: Res<TotalExpenses>,
+    days: Res<DaysSurvived>,
+) {
+    save.add_score(HighScore {
+        score: score(&gold, &expenses, &days),
+        profit: **gold - **expenses,
+        days: **days,
+    });
+}
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use bevy_mod_picking::prelude::*;
 use bevy_tweening::{
     lens::{TransformPositionLens, TransformRotateXLens},
@@ -10,9 +10,20 @@ use leafwing_input_manager::action_state::ActionState;
 use serde::Deserialize;
 use strum::{EnumCount, FromRepr};
 
-use crate::{input::Action, ui::tooltips::TooltipBundle, utils::Offset};
+use crate::{
+    assets::{GameData, ItemData},
+    input::Action,
+    ui::tooltips::{TooltipBundle, TooltipText},
+    utils::Offset,
+};
 
-use super::{scales::ScaleContents, GameState};
+use super::{
+    counter::{CounterContents, OnCounter},
+    history::{ScaleAction, ScaleHistory},
+    scales::{Index, OnScale, ScaleContents, SusEvent},
+    upgrades::Perks,
+    DailyExpenses, GameState,
+};
 
 #[derive(
     Component,
@@ -33,6 +44,80 @@ pub enum ItemType {
     GreenMush,
     SpiderEyes,
     VibrantSyrup,
+    Eggs,
+    Candles,
+}
+
+/// How an item is measured out for the customer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Weighed out on the scale
+    Grams,
+    /// Counted out on the counter
+    Count,
+}
+
+impl Unit {
+    /// How much comes in a freshly stocked batch, in grams or pieces
+    pub const fn batch_size(&self) -> f32 {
+        match self {
+            Self::Grams => 100.0,
+            Self::Count => 12.0,
+        }
+    }
+}
+
+impl ItemType {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        (0..Self::COUNT).filter_map(Self::from_repr)
+    }
+
+    /// Name of the item as written on its drawer
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Berries => "Berries",
+            Self::GreenMush => "Green Mush",
+            Self::SpiderEyes => "Spider Eyes",
+            Self::VibrantSyrup => "Vibrant Syrup",
+            Self::Eggs => "Eggs",
+            Self::Candles => "Candles",
+        }
+    }
+
+    pub const fn unit(&self) -> Unit {
+        match self {
+            Self::Eggs | Self::Candles => Unit::Count,
+            _ => Unit::Grams,
+        }
+    }
+
+    /// Write out an amount of this item, e.g. "5g of berries" or "3 candles"
+    pub fn describe(&self, amount: f32) -> String {
+        match self.unit() {
+            Unit::Grams => format!("{amount}g of {self}"),
+            Unit::Count if amount == 1.0 => format!("1 {}", self.singular()),
+            Unit::Count => format!("{amount} {self}"),
+        }
+    }
+
+    /// Short form of an amount for tickets and tooltips
+    pub fn format_amount(&self, amount: f32) -> String {
+        match self.unit() {
+            Unit::Grams => format!("{amount:.1} g"),
+            Unit::Count => format!("{amount:.0}"),
+        }
+    }
+
+    const fn singular(&self) -> &'static str {
+        match self {
+            Self::Eggs => "egg",
+            Self::Candles => "candle",
+            Self::Berries => "berry",
+            Self::GreenMush => "green mush",
+            Self::SpiderEyes => "spider eye",
+            Self::VibrantSyrup => "vibrant syrup",
+        }
+    }
 }
 
 impl std::fmt::Display for ItemType {
@@ -42,6 +127,8 @@ impl std::fmt::Display for ItemType {
             Self::GreenMush => write!(f, "green mush"),
             Self::SpiderEyes => write!(f, "spider eyes"),
             Self::VibrantSyrup => write!(f, "vibrant syrup"),
+            Self::Eggs => write!(f, "eggs"),
+            Self::Candles => write!(f, "candles"),
         }
     }
 }
@@ -98,10 +185,112 @@ pub const ITEM_COLORS: [Color; ItemType::COUNT] = [
     Color::DARK_GREEN,
     Color::SEA_GREEN,
     Color::rgb(1.0, 0.0, 1.0),
+    Color::ANTIQUE_WHITE,
+    Color::BISQUE,
 ];
 
-/// How much each item costs per 1 g
-pub const ITEM_COST: [f32; ItemType::COUNT] = [1.0, 2.0, 5.0, 8.0];
+/// How much each item costs per 1 g, or per piece for counted goods
+pub const ITEM_COST: [f32; ItemType::COUNT] = [1.0, 2.0, 5.0, 8.0, 3.0, 4.0];
+
+/// Color goods fade towards as they go off
+const ROTTEN_COLOR: Color = Color::rgb(0.3, 0.25, 0.1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Quality {
+    Fresh,
+    Good,
+    Stale,
+    Spoiled,
+}
+
+impl Quality {
+    /// Grade a score between 0.0 and 1.0, be it freshness or how well something was made
+    pub fn grade(score: f32) -> Self {
+        match score {
+            s if s >= 0.8 => Self::Fresh,
+            s if s >= 0.6 => Self::Good,
+            s if s >= 0.25 => Self::Stale,
+            _ => Self::Spoiled,
+        }
+    }
+}
+
+impl std::fmt::Display for Quality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fresh => write!(f, "fresh"),
+            Self::Good => write!(f, "good"),
+            Self::Stale => write!(f, "stale"),
+            Self::Spoiled => write!(f, "spoiled"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Batch {
+    /// 1.0 when freshly stocked, 0.0 when completely rotten
+    pub freshness: f32,
+    /// Grams, or pieces for counted goods, left in this batch
+    pub remaining: f32,
+}
+
+impl Batch {
+    /// A new batch of `t`, straight from the supplier
+    pub fn fresh(t: ItemType) -> Self {
+        Self {
+            freshness: 1.0,
+            remaining: t.unit().batch_size(),
+        }
+    }
+
+    pub fn quality(&self) -> Quality {
+        Quality::grade(self.freshness)
+    }
+}
+
+/// The batch currently sitting in each drawer
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct Stock(HashMap<ItemType, Batch>);
+
+impl Default for Stock {
+    fn default() -> Self {
+        Self(ItemType::iter().map(|t| (t, Batch::fresh(t))).collect())
+    }
+}
+
+impl Stock {
+    pub fn batch(&self, t: ItemType) -> Batch {
+        self.get(&t).copied().unwrap_or_else(|| Batch::fresh(t))
+    }
+
+    /// Take goods out of the drawers, opening a new batch once one runs out
+    pub fn take(&mut self, items: &HashMap<ItemType, f32>) {
+        for (t, amnt) in items.iter() {
+            let batch = self.entry(*t).or_insert_with(|| Batch::fresh(*t));
+            batch.remaining -= amnt;
+            if batch.remaining <= 0.0 {
+                *batch = Batch::fresh(*t);
+            }
+        }
+    }
+
+    /// Worst quality out of the given goods
+    pub fn quality_of<'a>(&self, items: impl IntoIterator<Item = &'a ItemType>) -> Quality {
+        items
+            .into_iter()
+            .map(|t| self.batch(*t).quality())
+            .max()
+            .unwrap_or(Quality::Fresh)
+    }
+
+    /// Lowest freshness out of the given goods
+    pub fn freshness<'a>(&self, items: impl IntoIterator<Item = &'a ItemType>) -> f32 {
+        items
+            .into_iter()
+            .map(|t| self.batch(*t).freshness)
+            .fold(1.0, f32::min)
+    }
+}
 
 pub struct GoodsPlugin;
 
@@ -111,7 +300,10 @@ impl Plugin for GoodsPlugin {
             .add_event::<RemoveItem>()
             .add_event::<Open>()
             .add_event::<Close>()
+            .init_resource::<Stock>()
             .add_systems(OnEnter(GameState::MainMenu), spawn_goods)
+            .add_systems(OnEnter(GameState::DayStart), age_stock)
+            .add_systems(OnEnter(GameState::Reset), restock)
             .add_systems(
                 Update,
                 (
@@ -119,6 +311,7 @@ impl Plugin for GoodsPlugin {
                     animate_drawers_close,
                     handle_add,
                     handle_remove,
+                    show_freshness.run_if(resource_changed::<Stock>()),
                 ),
             );
     }
@@ -189,19 +382,58 @@ fn spawn_goods(
         Offset::new(0.9, 0.0, 0.0),
         TooltipBundle::new("Vibrant Syrup"),
     ));
+
+    // counted goods
+    cmd.spawn((
+        PbrBundle {
+            mesh: box_mesh.clone(),
+            material: materials.add(ITEM_COLORS[ItemType::Eggs as usize].into()),
+            transform: DRAWER_CLOSED_POS,
+            ..default()
+        },
+        ItemType::Eggs,
+        On::<Pointer<Down>>::send_event::<AddItem>(),
+        On::<Pointer<Over>>::send_event::<Open>(),
+        On::<Pointer<Out>>::send_event::<Close>(),
+        Offset::new(1.2, 0.0, 0.0),
+        TooltipBundle::new("Eggs"),
+    ));
+    cmd.spawn((
+        PbrBundle {
+            mesh: box_mesh.clone(),
+            material: materials.add(ITEM_COLORS[ItemType::Candles as usize].into()),
+            transform: DRAWER_CLOSED_POS,
+            ..default()
+        },
+        ItemType::Candles,
+        On::<Pointer<Down>>::send_event::<AddItem>(),
+        On::<Pointer<Over>>::send_event::<Open>(),
+        On::<Pointer<Out>>::send_event::<Close>(),
+        Offset::new(1.5, 0.0, 0.0),
+        TooltipBundle::new("Candles"),
+    ));
 }
 
 fn handle_add(
     mut er: EventReader<AddItem>,
     q: Query<&ItemType>,
     mut contents: ResMut<ScaleContents>,
+    mut counter: ResMut<CounterContents>,
+    mut history: ResMut<ScaleHistory>,
     actions: Res<ActionState<Action>>,
+    perks: Res<Perks>,
 ) {
     for event in er.read() {
         let Ok(t) = q.get(event.0) else { continue };
 
+        if t.unit() == Unit::Count {
+            counter.put(*t, 1.0);
+            history.push(ScaleAction::AddItem(*t, 1.0));
+            continue;
+        }
+
         let amnt = if actions.pressed(Action::Mod) {
-            0.5
+            perks.fine_scoop
         } else {
             1.0
         };
@@ -211,25 +443,50 @@ fn handle_add(
         } else {
             contents.insert(*t, amnt);
         }
+
+        history.push(ScaleAction::AddItem(*t, amnt));
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_remove(
     mut er: EventReader<RemoveItem>,
     q: Query<&ItemType>,
     mut contents: ResMut<ScaleContents>,
+    mut counter: ResMut<CounterContents>,
+    mut history: ResMut<ScaleHistory>,
+    mut sus_events: EventWriter<SusEvent>,
     actions: Res<ActionState<Action>>,
+    perks: Res<Perks>,
 ) {
     for event in er.read() {
         let Ok(t) = q.get(event.0) else { continue };
 
+        if t.unit() == Unit::Count {
+            if actions.pressed(Action::Mod) {
+                // slip one back into the drawer, hoping the customer doesn't recount
+                if counter.palm(*t) {
+                    history.push(ScaleAction::Palm {
+                        item: *t,
+                        pile: event.0,
+                    });
+                    sus_events.send(SusEvent(Index(event.0)));
+                }
+            } else if counter.take(*t, 1.0) {
+                history.push(ScaleAction::RemoveItem(*t, 1.0));
+            }
+            continue;
+        }
+
         let amnt = if actions.pressed(Action::Mod) {
-            0.5
+            perks.fine_scoop
         } else {
             1.0
         };
 
         if let Some(val) = contents.get_mut(t) {
+            // only remember what was actually taken off the pile
+            history.push(ScaleAction::RemoveItem(*t, val.min(amnt)));
             *val -= amnt;
             // *val = val.max(0.0);
         } else {
@@ -299,3 +556,56 @@ fn animate_drawers_close(
         cmd.entity(ent).insert(Animator::new(slide_in));
     }
 }
+
+fn restock(mut stock: ResMut<Stock>) {
+    *stock = Stock::default();
+}
+
+fn age_stock(mut stock: ResMut<Stock>, data: Res<GameData>, items: Res<Assets<ItemData>>) {
+    let Some(items) = items.get(&data.items) else {
+        error!("Item data asset was missing");
+        return;
+    };
+
+    for (t, batch) in stock.iter_mut() {
+        batch.freshness = (batch.freshness - items.decay(*t)).max(0.0);
+    }
+}
+
+/// Throw out anything that has gone off and pay for a new batch
+pub fn write_off_spoiled(mut stock: ResMut<Stock>, mut expenses: ResMut<DailyExpenses>) {
+    for (t, batch) in stock.iter_mut() {
+        if batch.quality() == Quality::Spoiled {
+            info!(
+                "Wrote off {} of spoiled {t}",
+                t.format_amount(batch.remaining)
+            );
+            **expenses += batch.remaining * ITEM_COST[*t as usize];
+            *batch = Batch::fresh(*t);
+        }
+    }
+}
+
+fn show_freshness(
+    mut q: Query<
+        (&ItemType, &Handle<StandardMaterial>, &mut TooltipText),
+        (Without<OnScale>, Without<OnCounter>),
+    >,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    stock: Res<Stock>,
+) {
+    for (t, mat, mut txt) in q.iter_mut() {
+        let batch = stock.batch(*t);
+        txt.0 = format!("{} ({})", t.label(), batch.quality());
+
+        if let Some(mat) = materials.get_mut(mat) {
+            let fresh = ITEM_COLORS[*t as usize];
+            let rot = 1.0 - batch.freshness;
+            mat.base_color = Color::rgb(
+                fresh.r() + (ROTTEN_COLOR.r() - fresh.r()) * rot,
+                fresh.g() + (ROTTEN_COLOR.g() - fresh.g()) * rot,
+                fresh.b() + (ROTTEN_COLOR.b() - fresh.b()) * rot,
+            );
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+
+use crate::assets::CharacterTraits;
+
+use super::{
+    customer::{Customer, CustomerState, Purse},
+    PriceBoard, RepLog, Reputation, TargetProduct, TargetWeight,
+};
+
+/// Share of the usual price a trusting customer who isn't stingy pays on top
+const MAX_MARKUP: f32 = 0.3;
+/// Share of the usual price a stingy customer who distrusts the stall wants knocked off
+const MAX_DISCOUNT: f32 = 0.3;
+/// How far past what they'd pay an offer can go before they storm off
+const STORM_OFF: f32 = 1.5;
+/// Offers a customer hears out before giving up on the stall
+const PATIENCE: u32 = 3;
+/// Reputation lost when a customer storms off
+const REP_PENALTY: i32 = 2;
+/// Gold the asking price moves by with each press
+pub const PRICE_STEP: f32 = 5.0;
+
+/// Bargaining over the current order, before it's measured out
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Haggle {
+    /// The price the player is about to name
+    pub asking: f32,
+    /// The usual price of the order
+    pub list: f32,
+    /// Most the customer will agree to
+    willing: f32,
+    /// Offers the customer will still hear out
+    patience: u32,
+    /// The customer's last counter offer
+    pub counter: Option<f32>,
+    /// What the customer said to the last offer
+    pub remark: String,
+}
+
+/// The price the current order was sold for, once haggled over
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct AgreedPrice(Option<f32>);
+
+impl AgreedPrice {
+    /// The agreed price, or the usual price of the order before any haggling
+    pub fn or_list(
+        &self,
+        target: &TargetWeight,
+        product: &TargetProduct,
+        board: &PriceBoard,
+    ) -> f32 {
+        self.0
+            .unwrap_or_else(|| board.sale_price(target) + product.customer_cost())
+    }
+}
+
+/// The player names their price
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct Offer;
+
+/// The player settles for the customer's last counter offer
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct AcceptCounter;
+
+pub struct HagglePlugin;
+
+impl Plugin for HagglePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AgreedPrice>()
+            .add_event::<Offer>()
+            .add_event::<AcceptCounter>()
+            .add_systems(OnEnter(CustomerState::Haggle), start_haggle)
+            .add_systems(
+                Update,
+                (hear_offer, accept_counter)
+                    .run_if(resource_exists::<Haggle>().and_then(in_state(CustomerState::Haggle))),
+            )
+            .add_systems(OnExit(CustomerState::Haggle), |mut cmd: Commands| {
+                cmd.remove_resource::<Haggle>()
+            })
+            .add_systems(
+                OnEnter(CustomerState::End),
+                |mut agreed: ResMut<AgreedPrice>| *agreed = AgreedPrice::default(),
+            );
+    }
+}
+
+fn start_haggle(
+    mut cmd: Commands,
+    q: Query<(&Customer, &Purse)>,
+    chars: Res<Assets<CharacterTraits>>,
+    rep: Res<Reputation>,
+    target: Res<TargetWeight>,
+    product: Res<TargetProduct>,
+    board: Res<PriceBoard>,
+) {
+    let Ok((cust, purse)) = q.get_single() else {
+        return;
+    };
+    let Some(traits) = chars.get(&cust.0) else {
+        error!("Character traits asset was missing");
+        return;
+    };
+    let stingy = traits.stinginess;
+    let view = **rep as f32 / 100.0;
+    let list = board.sale_price(&target) + product.customer_cost();
+
+    // the more they trust the stall the more they'll stretch, unless they're tight with money
+    let willing =
+        list * (1.0 + MAX_MARKUP * view * (1.0 - stingy) - MAX_DISCOUNT * (1.0 - view) * stingy);
+
+    cmd.insert_resource(Haggle {
+        asking: list.round(),
+        list,
+        willing: willing.min(**purse),
+        patience: PATIENCE,
+        counter: None,
+        remark: traits.ask_price.clone(),
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn hear_offer(
+    mut er: EventReader<Offer>,
+    q: Query<&Customer>,
+    chars: Res<Assets<CharacterTraits>>,
+    mut haggle: ResMut<Haggle>,
+    mut agreed: ResMut<AgreedPrice>,
+    mut rep: ResMut<Reputation>,
+    mut log: ResMut<RepLog>,
+    mut state: ResMut<NextState<CustomerState>>,
+) {
+    for _ in er.read() {
+        let offer = haggle.asking;
+        if offer <= haggle.willing {
+            *agreed = AgreedPrice(Some(offer));
+            state.set(CustomerState::Measuring);
+            return;
+        }
+
+        haggle.patience = haggle.patience.saturating_sub(1);
+        if offer > haggle.willing * STORM_OFF || haggle.patience == 0 {
+            log.add("Haggled too hard", rep.change(-REP_PENALTY));
+            state.set(CustomerState::Walkout);
+            return;
+        }
+
+        // counter with a price the player can reach with the buttons
+        let counter = match (haggle.willing / PRICE_STEP).floor() * PRICE_STEP {
+            stepped if stepped > 0.0 => stepped,
+            _ => haggle.willing.floor(),
+        };
+        haggle.counter = Some(counter);
+        let line = q
+            .get_single()
+            .ok()
+            .and_then(|cust| chars.get(&cust.0))
+            .map_or("", |t| t.counter_offer.as_str());
+        haggle.remark = line
+            .replace("{offer}", &format!("{offer:.0}"))
+            .replace("{counter}", &format!("{counter:.0}"));
+    }
+}
+
+fn accept_counter(
+    mut er: EventReader<AcceptCounter>,
+    haggle: Res<Haggle>,
+    mut agreed: ResMut<AgreedPrice>,
+    mut state: ResMut<NextState<CustomerState>>,
+) {
+    for _ in er.read() {
+        if let Some(counter) = haggle.counter {
+            *agreed = AgreedPrice(Some(counter));
+            state.set(CustomerState::Measuring);
+        }
+    }
+}
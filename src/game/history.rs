@@ -0,0 +1,247 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use crate::input::Action;
+
+use super::{
+    counter::CounterContents,
+    customer::CustomerState,
+    goods::{ItemType, Unit},
+    scales::{AddWeight, Index, Mass, OnScale, RemoveWeight, ScaleContents, Sus, SusEvent},
+};
+
+/// A single change the player made to the scale
+#[derive(Debug, Clone)]
+pub enum ScaleAction {
+    AddItem(ItemType, f32),
+    RemoveItem(ItemType, f32),
+    /// A weight from the table was put on the scale, possibly knocking its counterpart off
+    AddWeight {
+        weight: Entity,
+        replaced: Option<Entity>,
+    },
+    /// A weight was taken off the scale
+    RemoveWeight(Entity),
+    /// A counted piece was slipped off the pile `pile`
+    Palm {
+        item: ItemType,
+        pile: Entity,
+    },
+    /// Everything was swept off the scale and counter at once
+    Clear {
+        contents: ScaleContents,
+        counter: CounterContents,
+        weights: Vec<Entity>,
+    },
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct ScaleHistory {
+    undo: Vec<ScaleAction>,
+    redo: Vec<ScaleAction>,
+}
+
+impl ScaleHistory {
+    pub fn push(&mut self, action: ScaleAction) {
+        self.undo.push(action);
+        self.redo.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// Everything needed to move things on and off the scale without recording it
+#[derive(SystemParam)]
+struct ScaleEditor<'w, 's> {
+    contents: ResMut<'w, ScaleContents>,
+    counter: ResMut<'w, CounterContents>,
+    table: Query<'w, 's, &'static Index, (With<Mass>, Without<OnScale>)>,
+    on_scale: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Visibility,
+            &'static Index,
+            Option<&'static Sus>,
+        ),
+        (With<OnScale>, Without<ItemType>),
+    >,
+    add_weight: EventWriter<'w, AddWeight>,
+    remove_weight: EventWriter<'w, RemoveWeight>,
+    sus_events: EventWriter<'w, SusEvent>,
+}
+
+impl ScaleEditor<'_, '_> {
+    fn put_item(&mut self, t: ItemType, amnt: f32) {
+        if t.unit() == Unit::Count {
+            self.counter.put(t, amnt);
+            return;
+        }
+
+        *self.contents.entry(t).or_default() += amnt;
+    }
+
+    fn take_item(&mut self, t: ItemType, amnt: f32) {
+        if t.unit() == Unit::Count {
+            self.counter.take(t, amnt);
+            return;
+        }
+
+        if let Some(val) = self.contents.get_mut(&t) {
+            *val -= amnt;
+        }
+        self.contents.retain(|_, v| *v > 0.0);
+    }
+
+    /// Put the table weight `weight` on the scale
+    fn place(&mut self, weight: Entity) {
+        // sus weights are reported by `add_weights` like any other placement
+        self.add_weight.send(AddWeight::untracked(weight));
+    }
+
+    /// Take the weight `on_scale` off the scale, back to its spot on the table
+    fn lift(&mut self, on_scale: Entity) {
+        if let Ok((_, _, _, sus)) = self.on_scale.get(on_scale) {
+            // the customer can still see a cheat weight being whisked away
+            if sus.is_some() {
+                self.sus_events.send(SusEvent(Index(on_scale)));
+            }
+            self.remove_weight.send(RemoveWeight::untracked(on_scale));
+        }
+    }
+
+    /// The table weight that belongs to the weight `on_scale`
+    fn table_weight(&self, on_scale: Entity) -> Option<Entity> {
+        self.on_scale.get(on_scale).ok().map(|(_, _, idx, _)| idx.0)
+    }
+
+    /// The weight on the scale that belongs to the table weight `weight`
+    fn scale_weight(&self, weight: Entity) -> Option<Entity> {
+        self.table.get(weight).ok().map(|idx| idx.0)
+    }
+
+    /// Table weights for everything currently sitting on the scale
+    fn placed_weights(&self) -> Vec<Entity> {
+        self.on_scale
+            .iter()
+            .filter(|(_, vis, _, _)| **vis != Visibility::Hidden)
+            .map(|(_, _, idx, _)| idx.0)
+            .collect()
+    }
+
+    fn sweep(&mut self) {
+        let placed = self
+            .on_scale
+            .iter()
+            .filter(|(_, vis, _, _)| **vis != Visibility::Hidden)
+            .map(|(ent, _, _, _)| ent)
+            .collect::<Vec<_>>();
+
+        for ent in placed {
+            self.lift(ent);
+        }
+
+        self.contents.clear();
+        *self.counter = CounterContents::default();
+    }
+
+    fn revert(&mut self, action: &ScaleAction) {
+        match action {
+            ScaleAction::AddItem(t, amnt) => self.take_item(*t, *amnt),
+            ScaleAction::RemoveItem(t, amnt) => self.put_item(*t, *amnt),
+            ScaleAction::AddWeight { weight, replaced } => {
+                if let Some(ent) = self.scale_weight(*weight) {
+                    self.lift(ent);
+                }
+                if let Some(ent) = replaced.and_then(|r| self.table_weight(r)) {
+                    self.place(ent);
+                }
+            }
+            ScaleAction::RemoveWeight(on_scale) => {
+                if let Some(ent) = self.table_weight(*on_scale) {
+                    self.place(ent);
+                }
+            }
+            ScaleAction::Palm { item, .. } => self.counter.unpalm(*item),
+            ScaleAction::Clear {
+                contents,
+                counter,
+                weights,
+            } => {
+                *self.contents = contents.clone();
+                *self.counter = counter.clone();
+                for w in weights {
+                    self.place(*w);
+                }
+            }
+        }
+    }
+
+    fn apply(&mut self, action: &ScaleAction) {
+        match action {
+            ScaleAction::AddItem(t, amnt) => self.put_item(*t, *amnt),
+            ScaleAction::RemoveItem(t, amnt) => self.take_item(*t, *amnt),
+            ScaleAction::AddWeight { weight, .. } => self.place(*weight),
+            ScaleAction::RemoveWeight(on_scale) => self.lift(*on_scale),
+            ScaleAction::Palm { item, pile } => {
+                if self.counter.palm(*item) {
+                    self.sus_events.send(SusEvent(Index(*pile)));
+                }
+            }
+            ScaleAction::Clear { .. } => self.sweep(),
+        }
+    }
+}
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScaleHistory>().add_systems(
+            Update,
+            (
+                undo.run_if(action_just_pressed(Action::Undo)),
+                redo.run_if(action_just_pressed(Action::Redo)),
+                clear_scale.run_if(action_just_pressed(Action::Clear)),
+            )
+                // the scale is only worked while measuring out an order
+                .run_if(in_state(CustomerState::Measuring)),
+        );
+    }
+}
+
+fn undo(mut history: ResMut<ScaleHistory>, mut editor: ScaleEditor) {
+    let Some(action) = history.undo.pop() else {
+        return;
+    };
+
+    editor.revert(&action);
+    history.redo.push(action);
+}
+
+fn redo(mut history: ResMut<ScaleHistory>, mut editor: ScaleEditor) {
+    let Some(action) = history.redo.pop() else {
+        return;
+    };
+
+    editor.apply(&action);
+    history.undo.push(action);
+}
+
+fn clear_scale(mut history: ResMut<ScaleHistory>, mut editor: ScaleEditor) {
+    let weights = editor.placed_weights();
+    if weights.is_empty() && editor.contents.is_empty() && editor.counter.is_empty() {
+        return;
+    }
+
+    history.push(ScaleAction::Clear {
+        contents: (*editor.contents).clone(),
+        counter: (*editor.counter).clone(),
+        weights,
+    });
+    editor.sweep();
+}
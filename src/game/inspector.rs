@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::utils::text_box::{SpawnTextBox, TextBox};
+
+use super::{
+    scales::{Confiscated, ScaleIsSus, Stashed, Sus},
+    ActiveDifficulty, CustomerState, GameRng, GameState, Ledger, RepLog, Reputation,
+};
+
+/// Odds of the inspector turning up as the stall opens
+const DAY_START_ODDS: f64 = 0.2;
+/// Odds of the inspector turning up after a customer leaves
+const BETWEEN_ODDS: f64 = 0.08;
+/// Seconds between the inspector being spotted and reaching the stall
+const WARNING_SECS: f32 = 4.0;
+/// Seconds the verdict stays up before the inspector moves on
+const VERDICT_SECS: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Stage {
+    /// Decided on, but the player hasn't been warned yet
+    #[default]
+    Coming,
+    /// Making their way over, there's still time to stash the weights
+    Spotted,
+    /// Done looking, the verdict is up
+    Inspected,
+}
+
+/// A visit from the market inspector, blocking customers until it's over
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Inspection {
+    stage: Stage,
+    timer: Timer,
+}
+
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::DayStart),
+            // the last of the day's rolls, after the customers are scheduled
+            maybe_inspect(DAY_START_ODDS).after(super::schedule_customer),
+        )
+        .add_systems(
+            OnEnter(CustomerState::End),
+            maybe_inspect(BETWEEN_ODDS).run_if(in_state(GameState::Customer)),
+        )
+        .add_systems(
+            Update,
+            inspect.run_if(resource_exists::<Inspection>().and_then(in_state(GameState::Waiting))),
+        )
+        .add_systems(OnEnter(GameState::DayEnd), end_inspection)
+        .add_systems(OnEnter(GameState::Reset), end_inspection);
+    }
+}
+
+fn maybe_inspect(odds: f64) -> impl FnMut(Commands, ResMut<GameRng>) {
+    move |mut cmd, mut rng| {
+        if rng.gen_bool(odds) {
+            cmd.init_resource::<Inspection>();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn inspect(
+    mut cmd: Commands,
+    mut inspection: ResMut<Inspection>,
+    mut ledger: ResMut<Ledger>,
+    mut rep: ResMut<Reputation>,
+    mut log: ResMut<RepLog>,
+    mut spawn_text: EventWriter<SpawnTextBox>,
+    boxes: Query<Entity, With<TextBox>>,
+    on_table: Query<&Visibility, (With<Sus>, Without<Stashed>, Without<Confiscated>)>,
+    on_scale: Option<Res<ScaleIsSus>>,
+    difficulty: Res<ActiveDifficulty>,
+    time: Res<Time>,
+) {
+    // warn once the customer's gone and their text box with them
+    if inspection.stage == Stage::Coming {
+        spawn_text.send("The market inspector is heading for your stall!".into());
+        *inspection = Inspection {
+            stage: Stage::Spotted,
+            timer: Timer::from_seconds(WARNING_SECS, TimerMode::Once),
+        };
+        return;
+    }
+
+    if !inspection.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if inspection.stage == Stage::Inspected {
+        end_inspection(cmd, boxes);
+        return;
+    }
+
+    for ent in boxes.iter() {
+        cmd.entity(ent).despawn_recursive();
+    }
+
+    // anything left out on the table or the scale gets noticed
+    let found = on_scale.is_some() || on_table.iter().any(|vis| *vis != Visibility::Hidden);
+    if found {
+        let fine = difficulty.inspection_fine;
+        ledger.push(("Inspection fine", fine));
+        log.add("Inspection", rep.change(-difficulty.inspection_penalty));
+        spawn_text.send(
+            format!(
+                "These weights are crooked! That's a {fine:.0} gold fine, and word will get around"
+            )
+            .into(),
+        );
+    } else {
+        log.add("Inspection", rep.change(difficulty.inspection_bonus));
+        spawn_text.send("All in order. I'll put up a notice that your scales are honest".into());
+    }
+
+    *inspection = Inspection {
+        stage: Stage::Inspected,
+        timer: Timer::from_seconds(VERDICT_SECS, TimerMode::Once),
+    };
+}
+
+fn end_inspection(mut cmd: Commands, boxes: Query<Entity, With<TextBox>>) {
+    cmd.remove_resource::<Inspection>();
+    for ent in boxes.iter() {
+        cmd.entity(ent).despawn_recursive();
+    }
+}
@@ -3,19 +3,53 @@ use rand::prelude::*;
 use serde::Deserialize;
 
 use crate::assets::CharacterTraits;
-use crate::assets::Characters;
-
-use self::{customer::CustomerPlugin, goods::GoodsPlugin, scales::ScalesPlugin};
-
+use crate::assets::{ArrivalCurve, Difficulties, Difficulty, GameData};
+
+use self::{
+    campaign::CampaignPlugin, coins::CoinsPlugin, counter::CounterPlugin, crafting::CraftingPlugin,
+    customer::CustomerPlugin, daily_events::DailyEventsPlugin, endless::EndlessPlugin,
+    goods::GoodsPlugin, haggle::HagglePlugin, history::HistoryPlugin, inspector::InspectorPlugin,
+    offenses::OffensesPlugin, prices::PricesPlugin, reputation::ReputationPlugin,
+    scales::ScalesPlugin, thieves::ThievesPlugin, till::TillPlugin, townsfolk::TownsfolkPlugin,
+    upgrades::UpgradesPlugin, weather::WeatherPlugin,
+};
+
+mod campaign;
+mod coins;
+mod counter;
+mod crafting;
 mod customer;
+mod daily_events;
+mod endless;
 mod goods;
+mod haggle;
+mod history;
+mod inspector;
+mod offenses;
+mod prices;
+mod reputation;
 mod scales;
-
+mod thieves;
+mod till;
+mod townsfolk;
+mod upgrades;
+mod weather;
+
+pub use campaign::{CurrentWeek, NextWeek, WeekProfit};
+pub use counter::CounterContents;
 pub use customer::CustomerState;
+pub use daily_events::Announcement;
+pub use endless::{score, DaysSurvived, GameMode};
 pub use goods::ITEM_COST;
-pub use goods::{AddItem, ItemType, RemoveItem};
+pub use goods::{AddItem, ItemType, RemoveItem, Unit};
+pub use haggle::{AcceptCounter, AgreedPrice, Haggle, Offer, PRICE_STEP};
+pub use offenses::{Consequences, Strikes};
+pub use prices::PriceBoard;
+pub use reputation::{Footfall, RepLog};
 pub use scales::ScaleContents;
 pub use scales::{AddWeight, RemoveWeight, Submit};
+pub use upgrades::{spendable, BuyUpgrade, Effect, Perks};
+pub use weather::Weather;
 
 #[derive(Resource, Default, Deref, DerefMut, Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct TotalGold(f32);
@@ -29,57 +63,149 @@ pub struct TotalExpenses(f32);
 #[derive(Resource, Default, Deref, DerefMut, Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DailyExpenses(f32);
 
+/// Dues charged on top of the day's expenses, like tax and rent
+#[derive(Resource, Default, Deref, DerefMut, Debug, Clone)]
+pub struct Ledger(Vec<(&'static str, f32)>);
+
+impl Ledger {
+    pub fn total(&self) -> f32 {
+        self.iter().map(|(_, amnt)| amnt).sum()
+    }
+}
+
+/// How the game ended, or is going to
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+    #[default]
+    Survived,
+    /// Couldn't pay what was due
+    Evicted,
+    /// Fell short of the week's goal
+    Missed,
+    /// Caught cheating one time too many
+    Arrested,
+}
+
+/// The difficulty preset the game is played on
+#[derive(Resource, Debug, Clone, Deref)]
+pub struct ActiveDifficulty(pub Difficulty);
+
 #[derive(Event, Default, Debug, Clone, Copy)]
 pub struct Advance;
 
 #[derive(Resource, Debug, Clone, Deref, Default)]
 pub struct TargetWeight(HashMap<ItemType, f32>);
 
-impl From<ItemRequest> for TargetWeight {
-    fn from(value: ItemRequest) -> Self {
-        Self(value.0)
+impl TargetWeight {
+    pub fn from_request(req: &ItemRequest, board: &PriceBoard) -> Self {
+        match req {
+            ItemRequest::Goods(goods) => Self(goods.clone()),
+            ItemRequest::Product(..) => Self::default(),
+            ItemRequest::Worth(t, gold) => Self(HashMap::from([(*t, board.worth(*t, *gold))])),
+        }
     }
 }
 
-impl From<&ItemRequest> for TargetWeight {
-    fn from(value: &ItemRequest) -> Self {
-        Self(value.0.clone())
+/// The crafted product the current customer asked for, if any
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TargetProduct {
+    pub name: String,
+    pub count: u32,
+    /// Full price of a single product
+    pub price: f32,
+}
+
+impl TargetProduct {
+    pub fn customer_cost(&self) -> f32 {
+        self.price * self.count as f32
     }
 }
 
 #[derive(Debug, Clone, Deserialize)]
-pub struct ItemRequest(pub(crate) HashMap<ItemType, f32>);
+pub enum ItemRequest {
+    /// Goods straight out of the drawers
+    Goods(HashMap<ItemType, f32>),
+    /// A number of crafted products, by recipe name
+    Product(String, u32),
+    /// As much of one item as the gold buys
+    Worth(ItemType, f32),
+}
+
+impl ItemRequest {
+    /// What the customer pays for the request, given the price of each product
+    pub fn cost(&self, board: &PriceBoard, product_price: impl Fn(&str) -> f32) -> f32 {
+        match self {
+            Self::Goods(goods) => board.sale_price(goods),
+            Self::Product(name, count) => product_price(name) * *count as f32,
+            Self::Worth(t, gold) => board.worth(*t, *gold) * board.price(*t),
+        }
+    }
+
+    /// How keen customers are to ask for this at the board prices
+    pub fn demand(&self, board: &PriceBoard) -> f32 {
+        match self {
+            Self::Goods(goods) if !goods.is_empty() => {
+                goods.keys().map(|t| board.demand(*t)).sum::<f32>() / goods.len() as f32
+            }
+            Self::Worth(t, _) => board.demand(*t),
+            _ => 1.0,
+        }
+    }
 
-impl std::ops::Deref for ItemRequest {
-    type Target = HashMap<ItemType, f32>;
+    /// Shrink the request to what `budget` covers, if any of it can be afforded
+    pub fn within(
+        &self,
+        budget: f32,
+        board: &PriceBoard,
+        product_price: impl Fn(&str) -> f32,
+    ) -> Option<Self> {
+        let cost = self.cost(board, &product_price);
+        if cost <= budget {
+            return Some(self.clone());
+        }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+        let request = match self {
+            Self::Goods(goods) => {
+                let factor = budget / cost;
+                let goods: HashMap<_, _> = goods
+                    .iter()
+                    .map(|(t, amount)| (*t, (amount * factor).floor()))
+                    .filter(|(_, amount)| *amount > 0.0)
+                    .collect();
+                Self::Goods(goods)
+            }
+            Self::Product(name, _) => {
+                Self::Product(name.clone(), (budget / product_price(name)) as u32)
+            }
+            Self::Worth(t, _) => Self::Worth(*t, budget),
+        };
+
+        // nothing left worth selling
+        match &request {
+            Self::Goods(goods) if goods.is_empty() => None,
+            Self::Product(_, 0) => None,
+            Self::Worth(t, gold) if board.worth(*t, *gold) == 0.0 => None,
+            _ => Some(request),
+        }
     }
 }
 
 impl std::fmt::Display for ItemRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut iter = self.iter().peekable();
+        let goods = match self {
+            Self::Goods(goods) => goods,
+            Self::Worth(t, gold) => return write!(f, "as much {t} as {gold:.0} gold buys"),
+            Self::Product(name, 1) => return write!(f, "a {name}"),
+            Self::Product(name, count) => return write!(f, "{count} {name}s"),
+        };
+
+        let mut iter = goods.iter().peekable();
 
         while let Some((t, amount)) = iter.next() {
-            if self.len() > 1 && iter.peek().is_none() {
+            if goods.len() > 1 && iter.peek().is_none() {
                 write!(f, "and ")?;
             }
-            match t {
-                ItemType::Berries => {
-                    write!(f, "{amount}g of berries")?;
-                }
-                ItemType::GreenMush => {
-                    write!(f, "{amount}g of green mush")?;
-                }
-                ItemType::SpiderEyes => {
-                    write!(f, "{amount}g of spider eyes")?;
-                }
-                ItemType::VibrantSyrup => {
-                    write!(f, "{amount}g of vibrant syrup")?;
-                }
-            }
+            write!(f, "{}", t.describe(*amount))?;
 
             if iter.peek().is_some() {
                 write!(f, ", ")?;
@@ -95,6 +221,8 @@ pub struct AttentionType {
     pub get_distracted: (u32, u32),
     pub get_focused: (u32, u32),
     pub threshold: f32,
+    /// How much freshness the goods can have lost before the customer notices
+    pub freshness: f32,
 }
 
 impl AttentionType {
@@ -107,6 +235,11 @@ impl AttentionType {
     fn sus_threshold(&self) -> f32 {
         self.threshold
     }
+
+    /// Returns how stale goods can be before the customer notices
+    fn stale_threshold(&self) -> f32 {
+        self.freshness
+    }
 }
 
 #[allow(dead_code)]
@@ -121,6 +254,8 @@ pub enum GameState {
     DayEnd,
     Customer,
     Dialogue,
+    Shop,
+    WeekEnd,
     GameOver,
     Error,
     Reset,
@@ -142,6 +277,18 @@ pub struct AvailableCustomers(Vec<Handle<CharacterTraits>>);
 pub struct Reputation(u8);
 
 impl Reputation {
+    /// Move reputation by `delta`, returning how far it actually moved
+    pub fn change(&mut self, delta: i32) -> i32 {
+        let old = self.0;
+        self.0 = (old as i32 + delta).clamp(0, 100) as u8;
+        self.0 as i32 - old as i32
+    }
+
+    /// How strongly the stall draws people in, 1 at middling reputation
+    pub fn pull(&self) -> f32 {
+        0.5 + self.0 as f32 / 100.0
+    }
+
     pub const fn sentiment(&self) -> &'static str {
         match self.0 {
             0..=10 => "angry",
@@ -152,103 +299,236 @@ impl Reputation {
     }
 }
 
-pub const DAY_LEN: f32 = 60.0;
-pub const WEEK_LEN: usize = 5;
+/// Multiplies how many customers turn up today
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut)]
+pub struct Turnout(f32);
+
+impl Default for Turnout {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Seed for the whole run, each day's rolls are drawn from it
+#[derive(Resource, Debug, Clone, Copy, Deref, Default)]
+pub struct RunSeed(u64);
+
+/// Randomness that should play out the same for the same run seed
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct GameRng(SmallRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(SmallRng::seed_from_u64(0))
+    }
+}
+
+pub const DEFAULT_DIFFICULTY: &str = "Normal";
+/// Fewest customers per second the stall ever sees, so the wait never runs away
+const MIN_ARRIVALS: f32 = 0.1;
 
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((CustomerPlugin, ScalesPlugin, GoodsPlugin))
-            .init_resource::<TotalGold>()
-            .init_resource::<DailyGold>()
-            .init_resource::<DailyExpenses>()
-            .init_resource::<TotalExpenses>()
-            .init_resource::<DayIndex>()
-            .init_resource::<AvailableCustomers>()
-            .add_state::<GameState>()
-            .add_event::<Advance>()
-            .insert_resource(CustomerTimer(Timer::from_seconds(5.0, TimerMode::Once)))
-            .insert_resource(DayTimer(Timer::from_seconds(DAY_LEN, TimerMode::Once)))
-            .insert_resource(Reputation(50))
-            .add_systems(
-                Update,
-                wait_for_customer.run_if(
-                    resource_exists::<CustomerTimer>().and_then(in_state(GameState::Waiting)),
-                ),
+        app.add_plugins((
+            CustomerPlugin,
+            ScalesPlugin,
+            GoodsPlugin,
+            HistoryPlugin,
+            CounterPlugin,
+            CraftingPlugin,
+            UpgradesPlugin,
+        ))
+        // the economy and everything that happens around the stall
+        .add_plugins((
+            CampaignPlugin,
+            EndlessPlugin,
+            OffensesPlugin,
+            InspectorPlugin,
+            ReputationPlugin,
+            TownsfolkPlugin,
+            HagglePlugin,
+            PricesPlugin,
+            CoinsPlugin,
+            TillPlugin,
+            ThievesPlugin,
+            DailyEventsPlugin,
+            WeatherPlugin,
+        ))
+        .init_resource::<TotalGold>()
+        .init_resource::<DailyGold>()
+        .init_resource::<DailyExpenses>()
+        .init_resource::<TotalExpenses>()
+        .init_resource::<DayIndex>()
+        .init_resource::<AvailableCustomers>()
+        .init_resource::<Ledger>()
+        .init_resource::<GameOutcome>()
+        .add_state::<GameState>()
+        .add_event::<Advance>()
+        .insert_resource(CustomerTimer(Timer::from_seconds(5.0, TimerMode::Once)))
+        .insert_resource(DayTimer(Timer::default()))
+        .init_resource::<Reputation>()
+        .init_resource::<Turnout>()
+        .init_resource::<RunSeed>()
+        .init_resource::<GameRng>()
+        .add_systems(
+            Update,
+            // customers hang back while the inspector is around
+            wait_for_customer.run_if(
+                resource_exists::<CustomerTimer>()
+                    .and_then(in_state(GameState::Waiting))
+                    .and_then(not(resource_exists::<inspector::Inspection>()))
+                    .and_then(not(resource_exists::<daily_events::Announcement>())),
+            ),
+        )
+        .add_systems(
+            OnEnter(GameState::DayEnd),
+            (
+                goods::write_off_spoiled,
+                charge_dues,
+                accounting,
+                campaign::tally_week,
+                check_bankruptcy,
+                campaign::check_goal,
             )
-            .add_systems(OnEnter(GameState::DayEnd), accounting)
-            .add_systems(OnEnter(GameState::GameOver), accounting)
-            .add_systems(
-                OnEnter(CustomerState::End),
-                customer_end.run_if(in_state(GameState::Customer)),
+                .chain(),
+        )
+        .add_systems(OnExit(GameState::Loading), default_difficulty)
+        .add_systems(OnEnter(GameState::GameOver), accounting)
+        .add_systems(
+            OnEnter(CustomerState::End),
+            (customer_end, schedule_customer).run_if(in_state(GameState::Customer)),
+        )
+        .add_systems(
+            Update,
+            (
+                // the day doesn't start until the news has been read
+                tick_day.run_if(not(resource_exists::<daily_events::Announcement>())),
+                finish_day.run_if(in_state(GameState::Waiting)),
+            ),
+        )
+        .add_systems(
+            OnEnter(GameState::DayStart),
+            // each day's rolls are drawn in the same order, so the seed plays out the same
+            (
+                start_day,
+                weather::roll_weather,
+                daily_events::roll_event,
+                schedule_customer,
             )
-            .add_systems(
-                Update,
-                (
-                    tick_day,
-                    set_available_cust.run_if(
-                        resource_exists::<Characters>().and_then(resource_changed::<Reputation>()),
-                    ),
-                    finish_day.run_if(in_state(GameState::Waiting)),
-                ),
-            )
-            .add_systems(OnEnter(GameState::DayStart), (start_day, customer_end))
-            .add_systems(OnEnter(GameState::Reset), reset);
+                .chain(),
+        )
+        .add_systems(OnEnter(GameState::Reset), reset);
     }
 }
 
+fn default_difficulty(
+    mut cmd: Commands,
+    difficulties: Res<Difficulties>,
+    presets: Res<Assets<Difficulty>>,
+) {
+    let normal = difficulties
+        .presets
+        .iter()
+        .filter_map(|h| presets.get(h))
+        .find(|d| d.name == DEFAULT_DIFFICULTY)
+        .or(difficulties.presets.first().and_then(|h| presets.get(h)))
+        .expect("No difficulty presets");
+    cmd.insert_resource(ActiveDifficulty(normal.clone()));
+}
+
+#[allow(clippy::too_many_arguments)]
 fn reset(
     mut day: ResMut<DayIndex>,
+    mut survived: ResMut<DaysSurvived>,
     mut total_e: ResMut<TotalExpenses>,
     mut total_g: ResMut<TotalGold>,
+    mut outcome: ResMut<GameOutcome>,
+    mut rep: ResMut<Reputation>,
     mut state: ResMut<NextState<GameState>>,
+    perks: Res<Perks>,
+    difficulty: Res<ActiveDifficulty>,
+    mut seed: ResMut<RunSeed>,
 ) {
     **day = 0;
+    seed.0 = thread_rng().gen();
+    info!("Run seed {}", seed.0);
+    **survived = 0;
+    // a new game, the shop sign is the first thing the village sees
+    **rep = reputation::baseline(&difficulty, &perks);
+    *outcome = GameOutcome::default();
     **total_e = 0.0;
     **total_g = 0.0;
     state.set(GameState::DayStart);
 }
 
+fn charge_dues(
+    mut ledger: ResMut<Ledger>,
+    gold: Res<DailyGold>,
+    day: Res<DayIndex>,
+    week: CurrentWeek,
+    difficulty: Res<ActiveDifficulty>,
+) {
+    if **gold > 0.0 {
+        ledger.push(("Market tax", **gold * difficulty.tax));
+    }
+
+    // the day index was already moved on to the next day
+    if let Some(current) = week.get().filter(|w| **day == w.days) {
+        ledger.push(("Stall rent", current.rent * difficulty.rent_factor));
+    }
+}
+
 fn accounting(
     mut total_g: ResMut<TotalGold>,
     mut total_e: ResMut<TotalExpenses>,
     daily_g: Res<DailyGold>,
     daily_e: Res<DailyExpenses>,
+    ledger: Res<Ledger>,
 ) {
     **total_g += **daily_g;
-    **total_e += **daily_e;
+    **total_e += **daily_e + ledger.total();
 }
 
-fn set_available_cust(
-    mut available: ResMut<AvailableCustomers>,
-    rep: Res<Reputation>,
-    customers: Res<Characters>,
+fn check_bankruptcy(
+    ledger: Res<Ledger>,
+    gold: Res<TotalGold>,
+    expenses: Res<TotalExpenses>,
+    mut outcome: ResMut<GameOutcome>,
 ) {
-    *available = match **rep {
-        0..=10 => AvailableCustomers(vec![customers.cop.clone()]),
-        11..=25 => AvailableCustomers(vec![customers.dumb.clone(), customers.cop.clone()]),
-        26..=50 => AvailableCustomers(vec![customers.normal.clone(), customers.dumb.clone()]),
-        _ => AvailableCustomers(vec![
-            customers.normal.clone(),
-            customers.attentive.clone(),
-            customers.dumb.clone(),
-        ]),
-    };
+    if !ledger.is_empty() && spendable(&gold, &expenses) < 0.0 {
+        info!("Couldn't pay {ledger:?}, evicted");
+        *outcome = GameOutcome::Evicted;
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn start_day(
     mut gold: ResMut<DailyGold>,
     mut expenses: ResMut<DailyExpenses>,
     mut state: ResMut<NextState<GameState>>,
     mut timer: ResMut<DayTimer>,
+    mut ledger: ResMut<Ledger>,
+    mut turnout: ResMut<Turnout>,
+    mut rng: ResMut<GameRng>,
+    footfall: Res<Footfall>,
+    seed: Res<RunSeed>,
     day: Res<DayIndex>,
+    outcome: Res<GameOutcome>,
 ) {
     **gold = 0.0;
     **expenses = 0.0;
+    ledger.clear();
     timer.reset();
+    // yesterday's customers have been talking
+    *turnout = Turnout(**footfall);
+    // the same run seed plays out the same days
+    *rng = GameRng(SmallRng::seed_from_u64(
+        **seed ^ (**day as u64).wrapping_mul(0x9E3779B97F4A7C15),
+    ));
 
-    if **day >= WEEK_LEN {
+    if *outcome != GameOutcome::Survived {
         state.set(GameState::GameOver);
     } else {
         state.set(GameState::Waiting);
@@ -258,11 +538,17 @@ fn start_day(
 fn finish_day(
     timer: Res<DayTimer>,
     mut day: ResMut<DayIndex>,
+    mut survived: ResMut<DaysSurvived>,
     mut state: ResMut<NextState<GameState>>,
+    outcome: Res<GameOutcome>,
 ) {
     info!("{timer:?}");
-    if timer.finished() {
+    // an arrest closes the stall on the spot
+    if timer.finished() || *outcome != GameOutcome::Survived {
         **day += 1;
+        if *outcome == GameOutcome::Survived {
+            **survived += 1;
+        }
         state.set(GameState::DayEnd);
     }
 }
@@ -271,11 +557,35 @@ fn tick_day(mut timer: ResMut<DayTimer>, time: Res<Time>) {
     timer.tick(time.delta());
 }
 
-fn customer_end(mut timer: ResMut<CustomerTimer>, mut state: ResMut<NextState<GameState>>) {
+fn customer_end(mut state: ResMut<NextState<GameState>>) {
     state.set(GameState::Waiting);
-    let mut rng = SmallRng::from_entropy();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn schedule_customer(
+    mut timer: ResMut<CustomerTimer>,
+    perks: Res<Perks>,
+    difficulty: Res<ActiveDifficulty>,
+    rep: Res<Reputation>,
+    turnout: Res<Turnout>,
+    day: Res<DayTimer>,
+    data: Res<GameData>,
+    curves: Res<Assets<ArrivalCurve>>,
+    mut rng: ResMut<GameRng>,
+) {
+    let (min, max) = difficulty.customer_wait;
+    // busier at some times of day than others
+    let rate = curves
+        .get(&data.arrivals)
+        .map_or(1.0, |c| c.rate(day.percent()))
+        * rep.pull()
+        * **turnout;
+    let usual = (min + max) / 2.0;
+    let arrivals = (rate / usual).max(MIN_ARRIVALS);
+    // arrivals at a steady rate are spaced out exponentially
+    let wait = -(1.0 - rng.gen::<f32>()).ln() / arrivals;
     *timer = CustomerTimer(Timer::from_seconds(
-        rng.gen_range(3.0..=10.0),
+        wait.clamp(min / 2.0, max * 2.0) * perks.customer_wait,
         TimerMode::Once,
     ));
 }
@@ -283,10 +593,12 @@ fn customer_end(mut timer: ResMut<CustomerTimer>, mut state: ResMut<NextState<Ga
 fn wait_for_customer(
     mut timer: ResMut<CustomerTimer>,
     time: Res<Time>,
+    day: Res<DayTimer>,
     curr_state: Res<State<GameState>>,
     mut state: ResMut<NextState<GameState>>,
 ) {
-    if **curr_state == GameState::Waiting {
+    // closing time, whoever is already at the counter still gets served
+    if **curr_state == GameState::Waiting && !day.finished() {
         timer.tick(time.delta());
 
         if timer.just_finished() {
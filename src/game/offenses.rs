@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::assets::CharacterTraits;
+
+use super::{scales::Confiscate, GameOutcome, GameState, Ledger};
+
+/// What getting caught by a character costs
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct Consequences {
+    /// Gold taken on the spot, paid with the day's dues
+    pub fine: f32,
+    /// Strikes added to the day's count
+    pub strikes: u32,
+    /// Strikes at which this character takes the sus weights for the rest of the day
+    pub confiscate_at: Option<u32>,
+    /// Strikes this character lets slide before making an arrest
+    pub strike_limit: Option<u32>,
+}
+
+impl Default for Consequences {
+    fn default() -> Self {
+        Self {
+            fine: 0.0,
+            strikes: 1,
+            confiscate_at: None,
+            strike_limit: None,
+        }
+    }
+}
+
+/// Times the player was caught cheating today
+#[derive(Resource, Debug, Clone, Copy, Deref, DerefMut, Default)]
+pub struct Strikes(u32);
+
+/// A customer caught the player cheating
+#[derive(Event, Debug, Clone)]
+pub struct Caught(pub Handle<CharacterTraits>);
+
+pub struct OffensesPlugin;
+
+impl Plugin for OffensesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Strikes>()
+            .add_event::<Caught>()
+            .add_systems(OnEnter(GameState::DayStart), clear_strikes)
+            .add_systems(Update, punish);
+    }
+}
+
+fn clear_strikes(mut strikes: ResMut<Strikes>) {
+    **strikes = 0;
+}
+
+fn punish(
+    mut er: EventReader<Caught>,
+    mut strikes: ResMut<Strikes>,
+    mut ledger: ResMut<Ledger>,
+    mut outcome: ResMut<GameOutcome>,
+    mut confiscate: EventWriter<Confiscate>,
+    chars: Res<Assets<CharacterTraits>>,
+) {
+    for Caught(handle) in er.read() {
+        let Some(traits) = chars.get(handle) else {
+            continue;
+        };
+        let caught = traits.caught;
+
+        **strikes += caught.strikes;
+        if caught.fine > 0.0 {
+            ledger.push(("Fine", caught.fine));
+        }
+
+        if caught.confiscate_at.is_some_and(|at| **strikes >= at) {
+            info!("{} took the sus weights", traits.name);
+            confiscate.send(Confiscate);
+        }
+
+        if caught.strike_limit.is_some_and(|limit| **strikes > limit) {
+            info!("Arrested by {} after {} strikes", traits.name, **strikes);
+            *outcome = GameOutcome::Arrested;
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::{prelude::*, utils::HashMap};
+use strum::EnumCount;
+
+use super::{GameState, ItemType, ITEM_COST};
+
+/// Reference sale price of goods over what they cost wholesale
+const MARKUP: f32 = 2.0;
+/// How sharply demand falls as prices rise over the reference price
+const ELASTICITY: f32 = 2.0;
+const MIN_DEMAND: f32 = 0.1;
+const MAX_DEMAND: f32 = 3.0;
+/// Gold a price on the board moves by with each press
+const BOARD_STEP: f32 = 1.0;
+
+/// Sale price of each good per gram or item, as chalked up by the player
+#[derive(Resource, Debug, Clone)]
+pub struct PriceBoard {
+    prices: [f32; ItemType::COUNT],
+    /// How much dearer goods in short supply are today
+    scarcity: [f32; ItemType::COUNT],
+}
+
+impl Default for PriceBoard {
+    fn default() -> Self {
+        Self {
+            prices: std::array::from_fn(|i| ITEM_COST[i] * MARKUP),
+            scarcity: [1.0; ItemType::COUNT],
+        }
+    }
+}
+
+impl PriceBoard {
+    /// The price customers expect to pay, going by the wholesale cost and what's scarce
+    pub fn reference(&self, t: ItemType) -> f32 {
+        ITEM_COST[t as usize] * MARKUP * self.scarcity[t as usize]
+    }
+
+    pub fn price(&self, t: ItemType) -> f32 {
+        self.prices[t as usize]
+    }
+
+    /// Marks a good as scarce for the day, the board goes up along with what people expect
+    pub fn shortage(&mut self, t: ItemType, factor: f32) {
+        self.scarcity[t as usize] *= factor;
+        self.prices[t as usize] = (self.prices[t as usize] * factor).round();
+    }
+
+    /// Back to normal supply, taking the bump for it back off the board
+    pub fn restock(&mut self) {
+        for (price, scarcity) in self.prices.iter_mut().zip(self.scarcity.iter_mut()) {
+            *price = (*price / *scarcity).round().max(BOARD_STEP);
+            *scarcity = 1.0;
+        }
+    }
+
+    pub fn raise(&mut self, t: ItemType) {
+        self.prices[t as usize] += BOARD_STEP;
+    }
+
+    pub fn lower(&mut self, t: ItemType) {
+        self.prices[t as usize] = (self.prices[t as usize] - BOARD_STEP).max(BOARD_STEP);
+    }
+
+    /// What a customer pays for the goods at the board prices
+    pub fn sale_price(&self, goods: &HashMap<ItemType, f32>) -> f32 {
+        goods.iter().map(|(t, amnt)| amnt * self.price(*t)).sum()
+    }
+
+    /// How much of an item a customer gets for `gold`, in whole grams or items
+    pub fn worth(&self, t: ItemType, gold: f32) -> f32 {
+        (gold / self.price(t)).floor()
+    }
+
+    /// How keen customers are on an item at its board price, 1 at the reference price
+    pub fn demand(&self, t: ItemType) -> f32 {
+        (self.reference(t) / self.price(t))
+            .powf(ELASTICITY)
+            .clamp(MIN_DEMAND, MAX_DEMAND)
+    }
+}
+
+pub struct PricesPlugin;
+
+impl Plugin for PricesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PriceBoard>().add_systems(
+            OnEnter(GameState::Reset),
+            |mut board: ResMut<PriceBoard>| *board = PriceBoard::default(),
+        );
+    }
+}
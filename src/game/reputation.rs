@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use super::{ActiveDifficulty, CustomerState, GameState, Perks, Reputation};
+
+/// Share of the gap to the baseline that reputation closes overnight
+const DRIFT: f32 = 0.25;
+/// Share of tomorrow's customers lost for every angry customer today
+const GOSSIP_LOSS: f32 = 0.1;
+/// Share of customers gained tomorrow for every customer served today
+const REFERRAL_GAIN: f32 = 0.04;
+const MIN_FOOTFALL: f32 = 0.5;
+const MAX_FOOTFALL: f32 = 2.0;
+
+/// Everything that moved reputation today, by reason
+#[derive(Resource, Debug, Clone, Default, Deref)]
+pub struct RepLog(Vec<(&'static str, i32)>);
+
+impl RepLog {
+    pub fn add(&mut self, reason: &'static str, delta: i32) {
+        if delta == 0 {
+            return;
+        }
+
+        match self.0.iter_mut().find(|(r, _)| *r == reason) {
+            Some((_, total)) => *total += delta,
+            None => self.0.push((reason, delta)),
+        }
+    }
+}
+
+/// Customers talking about the stall today, changes how busy tomorrow is
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct WordOfMouth {
+    pub gossip: u32,
+    pub referrals: u32,
+}
+
+/// Multiplies how many customers turn up tomorrow, set overnight by word of mouth
+#[derive(Resource, Debug, Clone, Copy, Deref)]
+pub struct Footfall(f32);
+
+impl Default for Footfall {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Reputation a game starts out at and drifts back toward
+pub fn baseline(difficulty: &ActiveDifficulty, perks: &Perks) -> u8 {
+    difficulty
+        .reputation
+        .saturating_add(perks.reputation)
+        .min(100)
+}
+
+pub struct ReputationPlugin;
+
+impl Plugin for ReputationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RepLog>()
+            .init_resource::<WordOfMouth>()
+            .init_resource::<Footfall>()
+            .add_systems(
+                OnEnter(CustomerState::Payment),
+                |mut mouth: ResMut<WordOfMouth>| mouth.referrals += 1,
+            )
+            .add_systems(
+                OnEnter(CustomerState::Angry),
+                |mut mouth: ResMut<WordOfMouth>| mouth.gossip += 1,
+            )
+            .add_systems(OnEnter(GameState::DayEnd), (drift, spread_word))
+            .add_systems(OnEnter(GameState::DayStart), new_day)
+            .add_systems(OnEnter(GameState::Reset), reset);
+    }
+}
+
+fn reset(mut footfall: ResMut<Footfall>) {
+    *footfall = Footfall::default();
+}
+
+fn new_day(mut log: ResMut<RepLog>, mut mouth: ResMut<WordOfMouth>) {
+    *log = RepLog::default();
+    *mouth = WordOfMouth::default();
+}
+
+fn drift(
+    mut rep: ResMut<Reputation>,
+    mut log: ResMut<RepLog>,
+    difficulty: Res<ActiveDifficulty>,
+    perks: Res<Perks>,
+) {
+    let gap = baseline(&difficulty, &perks) as f32 - **rep as f32;
+    // always move at least a point, so it does settle on the baseline
+    let step = (gap * DRIFT).round() as i32;
+    let step = if step == 0 { gap.signum() as i32 } else { step };
+
+    log.add("Settling overnight", rep.change(step));
+}
+
+fn spread_word(mouth: Res<WordOfMouth>, mut footfall: ResMut<Footfall>) {
+    let share = 1.0 - mouth.gossip as f32 * GOSSIP_LOSS + mouth.referrals as f32 * REFERRAL_GAIN;
+    *footfall = Footfall(share.clamp(MIN_FOOTFALL, MAX_FOOTFALL));
+}
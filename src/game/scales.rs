@@ -1,14 +1,16 @@
 use std::time::Duration;
 
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{prelude::*, transform::TransformSystem, utils::HashMap};
 use bevy_mod_picking::prelude::*;
 use bevy_tweening::{lens::TransformRotationLens, *};
 
 use crate::ui::tooltips::{TooltipBundle, TooltipText};
 
 use super::{
-    goods::{ItemType, RemoveItem, ITEM_COLORS},
-    GameState,
+    goods::{ItemType, RemoveItem, Unit, ITEM_COLORS},
+    history::{ScaleAction, ScaleHistory},
+    upgrades::Perks,
+    GameState, Weather,
 };
 
 pub const MAX_ROTATION_DEGREES: f32 = 30.0;
@@ -21,21 +23,59 @@ pub struct TablePoints(Vec<Transform>);
 #[derive(Resource, Deref, Debug)]
 pub struct ScalePoints(Vec<Transform>);
 
+/// Where the sus weights go when tucked away in the stash, lined up under the table
+#[derive(Resource, Deref, Debug)]
+pub struct StashPoints(Vec<Transform>);
+
 #[derive(Event, Clone, Debug)]
-pub struct AddWeight(Entity);
+pub struct AddWeight {
+    target: Entity,
+    /// Whether this change should be pushed to the undo history
+    tracked: bool,
+}
+
+impl AddWeight {
+    /// Place a weight without recording it, used when replaying the history
+    pub(super) fn untracked(target: Entity) -> Self {
+        Self {
+            target,
+            tracked: false,
+        }
+    }
+}
 
 impl From<ListenerInput<Pointer<Down>>> for AddWeight {
     fn from(value: ListenerInput<Pointer<Down>>) -> Self {
-        Self(value.target)
+        Self {
+            target: value.target,
+            tracked: true,
+        }
     }
 }
 
 #[derive(Event, Clone, Debug)]
-pub struct RemoveWeight(Entity);
+pub struct RemoveWeight {
+    target: Entity,
+    /// Whether this change should be pushed to the undo history
+    tracked: bool,
+}
+
+impl RemoveWeight {
+    /// Take a weight off without recording it, used when replaying the history
+    pub(super) fn untracked(target: Entity) -> Self {
+        Self {
+            target,
+            tracked: false,
+        }
+    }
+}
 
 impl From<ListenerInput<Pointer<Down>>> for RemoveWeight {
     fn from(value: ListenerInput<Pointer<Down>>) -> Self {
-        Self(value.target)
+        Self {
+            target: value.target,
+            tracked: true,
+        }
     }
 }
 
@@ -55,7 +95,7 @@ pub struct ScaleIsSus;
 pub struct Mass(f32);
 
 #[derive(Component, Debug, Clone, Copy, Deref)]
-pub struct Index(Entity);
+pub struct Index(pub(super) Entity);
 
 #[derive(Component, Debug, Clone, Copy, Deref)]
 pub struct Disables(Entity);
@@ -70,7 +110,36 @@ pub struct OnScale;
 pub struct Sus;
 
 #[derive(Event, Debug, Clone)]
-pub struct SusEvent(Index);
+pub struct SusEvent(pub(super) Index);
+
+/// Take the sus weights away for the rest of the day
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Confiscate;
+
+/// A sus weight that was taken away
+#[derive(Component, Debug)]
+pub struct Confiscated;
+
+/// Move the sus weights into the stash, or back out of it
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ToggleStash;
+
+impl From<ListenerInput<Pointer<Down>>> for ToggleStash {
+    fn from(_: ListenerInput<Pointer<Down>>) -> Self {
+        Self
+    }
+}
+
+/// A sus weight tucked away where an inspector won't look
+#[derive(Component, Debug)]
+pub struct Stashed;
+
+/// Where a sus weight sits out on the table and in the stash
+#[derive(Component, Debug, Clone, Copy)]
+pub struct StashSpot {
+    table: Vec3,
+    stash: Vec3,
+}
 
 #[derive(Resource, Default, Debug, Clone, Copy)]
 pub struct ScaleWeights {
@@ -90,6 +159,13 @@ pub struct Scales;
 
 pub const WEIGHT_HEIGHT: f32 = 0.125;
 pub const WEIGHT_RAD: f32 = 0.05;
+const SUS_OFFSET: Vec3 = Vec3::new(0.0, 0.0, -0.2);
+/// From where the sus weights sit on the table to their spot in the stash
+const STASH_OFFSET: Vec3 = Vec3::new(0.0, -0.8, 0.7);
+
+/// How far the wind is tipping the beam this frame, taken off again before anything else moves it
+#[derive(Component, Debug, Default)]
+struct Gust(f32);
 
 pub struct ScalesPlugin;
 
@@ -99,9 +175,12 @@ impl Plugin for ScalesPlugin {
             .add_event::<RemoveWeight>()
             .add_event::<Submit>()
             .add_event::<SusEvent>()
+            .add_event::<Confiscate>()
+            .add_event::<ToggleStash>()
             .init_resource::<ScaleWeights>()
             .init_resource::<ScaleContents>()
             .add_systems(OnEnter(GameState::MainMenu), setup_scales)
+            .add_systems(OnEnter(GameState::DayStart), return_confiscated)
             // .add_systems(PostUpdate, place_weights.after(TransformSystem::TransformPropagate))
             .add_systems(
                 Update,
@@ -111,11 +190,19 @@ impl Plugin for ScalesPlugin {
                     update_scale_rot,
                     scale_piles,
                     update_sus.run_if(resource_changed::<ScaleWeights>()),
+                    confiscate,
+                    toggle_stash,
                 ),
             )
             .add_systems(
                 Update,
                 (set_weight).run_if(resource_exists_and_changed::<ScaleContents>()),
+            )
+            // the wind only rocks the beam on screen, the weighing itself stays fair
+            .add_systems(PreUpdate, calm_beam)
+            .add_systems(
+                PostUpdate,
+                sway_beam.before(TransformSystem::TransformPropagate),
             );
 
         let mut table_points = vec![];
@@ -131,6 +218,12 @@ impl Plugin for ScalesPlugin {
         }
 
         app.insert_resource(TablePoints(table_points.clone()));
+        app.insert_resource(StashPoints(
+            table_points
+                .iter()
+                .map(|tr| tr.with_translation(tr.translation + STASH_OFFSET))
+                .collect(),
+        ));
 
         let mut scale_points = vec![];
         let mut row = -0.5;
@@ -157,12 +250,14 @@ impl Plugin for ScalesPlugin {
 pub fn reset(
     mut scale_weights: ResMut<ScaleWeights>,
     mut contents: ResMut<ScaleContents>,
-    mut table_masses: Query<&mut Visibility, (With<Mass>, Without<OnScale>)>,
+    mut history: ResMut<ScaleHistory>,
+    mut table_masses: Query<&mut Visibility, (With<Mass>, Without<OnScale>, Without<Confiscated>)>,
     mut scale_masses: Query<&mut Visibility, (With<OnScale>, Without<ItemType>)>,
 ) {
     scale_weights.left = 0.0;
     scale_weights.right = 0.0;
     *contents = ScaleContents::default();
+    history.clear();
 
     // cmd.entity(ent).remove_children(&masses.iter().collect::<Vec<Entity>>());
     for mut m in table_masses.iter_mut() {
@@ -181,6 +276,7 @@ fn setup_scales(
     mut materials: ResMut<Assets<StandardMaterial>>,
     table_points: Res<TablePoints>,
     scale_points: Res<ScalePoints>,
+    stash_points: Res<StashPoints>,
 ) {
     let mesh = meshes.add(Mesh::from(shape::Box::new(3.0, 1.0, 1.0)));
     let mat = materials.add(Color::BEIGE.into());
@@ -204,6 +300,7 @@ fn setup_scales(
             ..default()
         },
         Scales,
+        Gust::default(),
     ))
     .with_children(|parent| {
         let mut shift: usize = WEIGHTS.len();
@@ -238,7 +335,9 @@ fn setup_scales(
                         PbrBundle {
                             mesh: weight_mesh.clone(),
                             material: sus_mat.clone(),
-                            transform: scale_points[shift],
+                            // just behind the honest weight, in case they share the scale
+                            transform: scale_points[shift]
+                                .with_translation(scale_points[shift].translation + SUS_OFFSET),
                             visibility: Visibility::Hidden,
                             ..default()
                         },
@@ -257,6 +356,11 @@ fn setup_scales(
                 continue;
             };
 
+            // counted goods go on the counter instead
+            if t.unit() != Unit::Grams {
+                continue;
+            }
+
             if i as f32 % 2.0 == 0.0 {
                 row += 1.0;
             }
@@ -342,6 +446,10 @@ fn setup_scales(
                 Disables(disable),
                 TooltipBundle::new(format!("{} grams", w / 2.0)),
                 Sus,
+                StashSpot {
+                    table: translation,
+                    stash: stash_points[shift].translation,
+                },
                 On::<Pointer<Down>>::send_event::<AddWeight>(),
             ))
             .id();
@@ -350,6 +458,32 @@ fn setup_scales(
         off_scale_sus.push(ent);
     }
 
+    // the stash, a box under the table the sus weights can be swept into
+    let first = stash_points
+        .first()
+        .map(|tr| tr.translation)
+        .unwrap_or_default();
+    let last = stash_points
+        .last()
+        .map(|tr| tr.translation)
+        .unwrap_or_default();
+    cmd.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Box::new(
+                (last.x - first.x).abs() + WEIGHT_RAD * 4.0,
+                0.05,
+                WEIGHT_RAD * 4.0,
+            ))),
+            material: materials.add(Color::rgb(0.35, 0.2, 0.1).into()),
+            transform: Transform::from_translation(
+                (first + last) / 2.0 - Vec3::new(0.0, WEIGHT_HEIGHT, 0.0),
+            ),
+            ..default()
+        },
+        TooltipBundle::new("Stash"),
+        On::<Pointer<Down>>::send_event::<ToggleStash>(),
+    ));
+
     // submit bell thing
     let bell_mesh = meshes.add(
         shape::Icosphere {
@@ -372,26 +506,47 @@ fn setup_scales(
     ));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_weights(
     mut free_weights: Query<
         (&mut Visibility, &Index, &Mass, &Disables, Option<&Sus>),
-        Without<OnScale>,
+        (Without<OnScale>, Without<Stashed>),
     >,
     mut used_weights: Query<&mut Visibility, With<OnScale>>,
     mut scale_weights: ResMut<ScaleWeights>,
+    mut history: ResMut<ScaleHistory>,
     mut events: EventReader<AddWeight>,
     mut sus_events: EventWriter<SusEvent>,
     mut remove_weight: EventWriter<RemoveWeight>,
+    perks: Res<Perks>,
 ) {
-    for AddWeight(ent) in events.read() {
+    for AddWeight {
+        target: ent,
+        tracked,
+    } in events.read()
+    {
         if let Ok((mut vis, idx, m, disable, sus)) = free_weights.get_mut(*ent) {
+            if *vis == Visibility::Hidden {
+                continue;
+            }
             *vis = Visibility::Hidden;
             scale_weights.left += **m;
 
             info!("Num weights on scale = {}", used_weights.iter().count());
 
-            if Ok(&Visibility::Visible) == used_weights.get(disable.0) {
-                remove_weight.send(RemoveWeight(disable.0));
+            let replaced =
+                if !perks.stack_sus && Ok(&Visibility::Visible) == used_weights.get(disable.0) {
+                    remove_weight.send(RemoveWeight::untracked(disable.0));
+                    Some(disable.0)
+                } else {
+                    None
+                };
+
+            if *tracked {
+                history.push(ScaleAction::AddWeight {
+                    weight: *ent,
+                    replaced,
+                });
             }
 
             if let Ok(mut vis) = used_weights.get_mut(idx.0) {
@@ -409,24 +564,44 @@ fn add_weights(
 
 fn remove_weights(
     mut free_weights: Query<(&mut Visibility, &Index, &Mass), (Without<OnScale>, Without<Sus>)>,
-    mut free_sus_weights: Query<(&mut Visibility, &Index, &Mass), (Without<OnScale>, With<Sus>)>,
+    mut free_sus_weights: Query<
+        (&mut Visibility, &Index, &Mass, Has<Confiscated>),
+        (Without<OnScale>, With<Sus>),
+    >,
 
     mut used_weights: Query<(&mut Visibility, &Index, Option<&Sus>), With<OnScale>>,
     mut scale_weights: ResMut<ScaleWeights>,
+    mut history: ResMut<ScaleHistory>,
     mut events: EventReader<RemoveWeight>,
 ) {
-    for RemoveWeight(ent) in events.read() {
+    for RemoveWeight {
+        target: ent,
+        tracked,
+    } in events.read()
+    {
         if let Ok((mut vis, idx, sus)) = used_weights.get_mut(*ent) {
+            if *vis == Visibility::Hidden {
+                continue;
+            }
             *vis = Visibility::Hidden;
 
+            if *tracked {
+                history.push(ScaleAction::RemoveWeight(*ent));
+            }
+
             let off_scale = if sus.is_some() {
                 free_sus_weights.get_mut(idx.0)
             } else {
-                free_weights.get_mut(idx.0)
+                free_weights
+                    .get_mut(idx.0)
+                    .map(|(vis, idx, m)| (vis, idx, m, false))
             };
 
-            if let Ok((mut vis, _, m)) = off_scale {
-                *vis = Visibility::Visible;
+            if let Ok((mut vis, _, m, confiscated)) = off_scale {
+                // confiscated weights leave the scale but don't go back on the table
+                if !confiscated {
+                    *vis = Visibility::Visible;
+                }
                 scale_weights.left -= **m;
             } else {
                 warn!("Couldn't find weight on scale");
@@ -451,6 +626,52 @@ fn scale_piles(
     }
 }
 
+fn confiscate(
+    mut cmd: Commands,
+    mut er: EventReader<Confiscate>,
+    mut q: Query<(Entity, &mut Visibility), (With<Sus>, Without<OnScale>)>,
+) {
+    if er.read().last().is_none() {
+        return;
+    }
+
+    for (ent, mut vis) in q.iter_mut() {
+        *vis = Visibility::Hidden;
+        cmd.entity(ent).insert(Confiscated);
+    }
+}
+
+fn toggle_stash(
+    mut cmd: Commands,
+    mut er: EventReader<ToggleStash>,
+    mut q: Query<(Entity, &mut Transform, &StashSpot, Has<Stashed>), Without<Confiscated>>,
+) {
+    if er.read().last().is_none() {
+        return;
+    }
+
+    for (ent, mut tr, spot, stashed) in q.iter_mut() {
+        if stashed {
+            tr.translation = spot.table;
+            cmd.entity(ent).remove::<Stashed>();
+        } else {
+            tr.translation = spot.stash;
+            cmd.entity(ent).insert(Stashed);
+        }
+    }
+}
+
+/// The scale is empty between days, so everything goes back on the table
+fn return_confiscated(
+    mut cmd: Commands,
+    mut q: Query<(Entity, &mut Visibility), (With<Confiscated>, Without<OnScale>)>,
+) {
+    for (ent, mut vis) in q.iter_mut() {
+        *vis = Visibility::Visible;
+        cmd.entity(ent).remove::<Confiscated>();
+    }
+}
+
 fn update_sus(q: Query<&Visibility, (With<Sus>, With<OnScale>)>, mut cmd: Commands) {
     let sus_count = q.iter().filter(|vis| **vis != Visibility::Hidden).count();
     if sus_count > 0 {
@@ -502,3 +723,25 @@ fn update_scale_rot(
         cmd.entity(ent).insert(Animator::new(tween));
     }
 }
+
+fn calm_beam(mut q: Query<(&mut Transform, &mut Gust)>) {
+    for (mut tr, mut gust) in q.iter_mut() {
+        tr.rotation = Quat::from_rotation_z(-gust.0) * tr.rotation;
+        gust.0 = 0.0;
+    }
+}
+
+fn sway_beam(mut q: Query<(&mut Transform, &mut Gust)>, weather: Res<Weather>, time: Res<Time>) {
+    let strength = weather.gusts().to_radians();
+    if strength == 0.0 {
+        return;
+    }
+
+    // a couple of out of step waves so it doesn't rock like a metronome
+    let t = time.elapsed_seconds();
+    let angle = strength * (0.6 * (t * 2.3).sin() + 0.4 * (t * 5.9).sin());
+    for (mut tr, mut gust) in q.iter_mut() {
+        tr.rotation = Quat::from_rotation_z(angle) * tr.rotation;
+        gust.0 = angle;
+    }
+}
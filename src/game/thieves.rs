@@ -0,0 +1,255 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_mod_picking::prelude::*;
+use bevy_tweening::{lens::TransformPositionLens, *};
+use rand::prelude::*;
+
+use crate::{
+    crowd::{self, CrowdMember, ROAD_OFFSET},
+    utils::CalcCost,
+};
+
+use super::{
+    coins::DENOMINATIONS, customer::CustomerState, goods::Stock, till::Till, GameRng, GameState,
+    ItemType, Ledger, RepLog, Reputation, Unit,
+};
+
+/// Corner of the stand thieves sneak up to, away from the customer
+const STAND_SIDE: Vec3 = Vec3::new(-1.6, 1.0, -1.4);
+const SNEAK_SECS: f32 = 2.5;
+/// How long a thief lingers at the stand before grabbing something
+const GRAB_SECS: f32 = 2.0;
+const FLEE_SECS: f32 = 1.5;
+/// How often someone in the crowd considers trying their luck
+const TEMPTATION_SECS: f32 = 4.0;
+const THIEF_ODDS: f64 = 0.2;
+/// Most coins a thief manages to scoop out of the till
+const MAX_COINS: usize = 3;
+/// Reputation gained for catching a thief in the act
+const CATCH_REP: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Loot {
+    Goods(ItemType, f32),
+    Till,
+}
+
+/// A crowd member who left the road to steal from the stall
+#[derive(Component, Debug)]
+pub struct Thief {
+    loot: Loot,
+    timer: Timer,
+    fleeing: bool,
+    caught: bool,
+}
+
+/// Grab a thief by the collar
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CatchThief(Entity);
+
+impl From<ListenerInput<Pointer<Down>>> for CatchThief {
+    fn from(value: ListenerInput<Pointer<Down>>) -> Self {
+        Self(value.target)
+    }
+}
+
+/// What thieves got away with today, goods at what they cost
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct Stolen {
+    pub goods: f32,
+    pub coins: f32,
+}
+
+impl Stolen {
+    pub fn total(&self) -> f32 {
+        self.goods + self.coins
+    }
+}
+
+pub struct ThievesPlugin;
+
+impl Plugin for ThievesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CatchThief>()
+            .init_resource::<Stolen>()
+            .add_systems(
+                Update,
+                (
+                    // only while the player has their hands full with a customer
+                    tempt_crowd.run_if(
+                        in_state(CustomerState::Haggle)
+                            .or_else(in_state(CustomerState::Measuring))
+                            .or_else(in_state(CustomerState::Coins)),
+                    ),
+                    catch_thief,
+                    steal,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                OnEnter(GameState::DayStart),
+                |mut stolen: ResMut<Stolen>| *stolen = Stolen::default(),
+            )
+            .add_systems(
+                OnEnter(GameState::DayEnd),
+                (report_theft.before(super::accounting), send_home),
+            );
+    }
+}
+
+fn walk_to(from: Vec3, to: Vec3, secs: f32) -> Animator<Transform> {
+    Animator::new(Tween::new(
+        EaseFunction::QuadraticInOut,
+        Duration::from_secs_f32(secs),
+        TransformPositionLens {
+            start: from,
+            end: to,
+        },
+    ))
+}
+
+/// Sends one of the passers-by sneaking up to the stand while the player has their hands full
+fn tempt_crowd(
+    mut cmd: Commands,
+    crowd_q: Query<(Entity, &Transform), (With<CrowdMember>, Without<Thief>)>,
+    thief_q: Query<(), With<Thief>>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut timer: Local<Timer>,
+) {
+    if timer.mode() != TimerMode::Repeating {
+        *timer = Timer::from_seconds(TEMPTATION_SECS, TimerMode::Repeating);
+    }
+    if !timer.tick(time.delta()).just_finished() || !thief_q.is_empty() {
+        return;
+    }
+
+    if !rng.gen_bool(THIEF_ODDS) {
+        return;
+    }
+    let Some((ent, tr)) = crowd_q.iter().choose(&mut **rng) else {
+        return;
+    };
+
+    let loot = if rng.gen_bool(0.5) {
+        Loot::Till
+    } else {
+        let t = ItemType::iter().choose(&mut **rng).expect("No goods");
+        let amount = match t.unit() {
+            Unit::Grams => rng.gen_range(20..=60) as f32,
+            Unit::Count => rng.gen_range(1..=3) as f32,
+        };
+        Loot::Goods(t, amount)
+    };
+
+    cmd.entity(ent).insert((
+        Thief {
+            loot,
+            timer: Timer::from_seconds(SNEAK_SECS + GRAB_SECS, TimerMode::Once),
+            fleeing: false,
+            caught: false,
+        },
+        walk_to(tr.translation, STAND_SIDE, SNEAK_SECS),
+        On::<Pointer<Down>>::send_event::<CatchThief>(),
+    ));
+}
+
+fn flee(cmd: &mut Commands, ent: Entity, thief: &mut Thief, from: Vec3) {
+    let edge = if from.x < 0.0 { -10.0 } else { 10.0 };
+    thief.fleeing = true;
+    thief.timer = Timer::from_seconds(FLEE_SECS, TimerMode::Once);
+    cmd.entity(ent)
+        .insert(walk_to(from, Vec3::new(edge, 1.0, ROAD_OFFSET), FLEE_SECS));
+}
+
+fn catch_thief(
+    mut cmd: Commands,
+    mut er: EventReader<CatchThief>,
+    mut thief_q: Query<(&mut Thief, &Transform)>,
+    mut rep: ResMut<Reputation>,
+    mut log: ResMut<RepLog>,
+) {
+    for CatchThief(ent) in er.read() {
+        let Ok((mut thief, tr)) = thief_q.get_mut(*ent) else {
+            continue;
+        };
+        if thief.caught {
+            continue;
+        }
+
+        // they drop whatever they grabbed and run for it
+        thief.caught = true;
+        flee(&mut cmd, *ent, &mut thief, tr.translation);
+        log.add("Caught thieves", rep.change(CATCH_REP));
+    }
+}
+
+fn steal(
+    mut cmd: Commands,
+    mut thief_q: Query<(Entity, &mut Thief, &Transform)>,
+    mut stock: ResMut<Stock>,
+    mut till: ResMut<Till>,
+    mut stolen: ResMut<Stolen>,
+    mut rng: ResMut<GameRng>,
+    time: Res<Time>,
+) {
+    for (ent, mut thief, tr) in thief_q.iter_mut() {
+        if !thief.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        if !thief.fleeing {
+            flee(&mut cmd, ent, &mut thief, tr.translation);
+            continue;
+        }
+
+        // made it back to the road
+        if !thief.caught {
+            match thief.loot {
+                Loot::Goods(t, amount) => {
+                    // can't make off with more than is left in the drawer
+                    let amount = amount.min(stock.batch(t).remaining);
+                    if amount <= 0.0 {
+                        rejoin_crowd(&mut cmd, ent, tr.translation);
+                        continue;
+                    }
+                    let goods = HashMap::from([(t, amount)]);
+                    stock.take(&goods);
+                    stolen.goods += goods.cost();
+                }
+                Loot::Till => {
+                    for _ in 0..MAX_COINS {
+                        let slot = rng.gen_range(0..DENOMINATIONS.len());
+                        stolen.coins += till.take(slot).unwrap_or_default();
+                    }
+                }
+            }
+        }
+        rejoin_crowd(&mut cmd, ent, tr.translation);
+    }
+}
+
+fn rejoin_crowd(cmd: &mut Commands, ent: Entity, pos: Vec3) {
+    cmd.entity(ent)
+        .remove::<(Thief, On<Pointer<Down>>)>()
+        .insert(Animator::new(crowd::stroll(
+            &mut thread_rng(),
+            pos.x,
+            pos.z,
+            ent.index() as u64,
+        )));
+}
+
+/// Nobody's left lurking around the stand overnight
+fn send_home(mut cmd: Commands, thief_q: Query<Entity, With<Thief>>) {
+    for ent in thief_q.iter() {
+        rejoin_crowd(&mut cmd, ent, Vec3::new(10.0, 1.0, ROAD_OFFSET));
+    }
+}
+
+fn report_theft(stolen: Res<Stolen>, mut ledger: ResMut<Ledger>) {
+    if stolen.total() > 0.0 {
+        ledger.push(("Theft", stolen.total()));
+    }
+}
@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::ui::tooltips::{TooltipBundle, TooltipText};
+
+use super::{
+    coins::{self, Coin, DENOMINATIONS},
+    customer::CustomerState,
+    thieves::Stolen,
+    DailyGold, GameState, Ledger,
+};
+
+const TILL_POS: Vec3 = Vec3::new(-0.4, 1.0, -0.2);
+const SLOT_SPACING: f32 = 0.14;
+const TRAY_HEIGHT: f32 = 0.02;
+/// Coins the till is opened with each morning, by denomination, so there's change to give
+const OPENING_FLOAT: [u32; DENOMINATIONS.len()] = [5, 4, 2, 0];
+/// Tallest a stack of coins is drawn, however many are in it
+const MAX_STACK: u32 = 20;
+
+/// Coins sorted into the till, counted by denomination
+#[derive(Resource, Debug, Clone)]
+pub struct Till([u32; DENOMINATIONS.len()]);
+
+impl Default for Till {
+    fn default() -> Self {
+        Self(OPENING_FLOAT)
+    }
+}
+
+impl Till {
+    pub fn total(&self) -> f32 {
+        self.0
+            .iter()
+            .zip(DENOMINATIONS)
+            .map(|(n, face)| *n as f32 * face)
+            .sum()
+    }
+
+    pub fn float() -> f32 {
+        Self::default().total()
+    }
+
+    pub fn put(&mut self, face: f32) {
+        if let Some(i) = DENOMINATIONS.iter().position(|d| *d == face) {
+            self.0[i] += 1;
+        }
+    }
+
+    /// Takes a coin out of a slot, if there are any left in it
+    pub fn take(&mut self, slot: usize) -> Option<f32> {
+        let n = self.0.get_mut(slot).filter(|n| **n > 0)?;
+        *n -= 1;
+        Some(DENOMINATIONS[slot])
+    }
+}
+
+/// One slot of the till, holding a single denomination
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TillSlot(usize);
+
+/// Take a coin out of the till to give as change
+#[derive(Event, Debug, Clone, Copy)]
+pub struct TakeChange(Entity);
+
+impl From<ListenerInput<Pointer<Down>>> for TakeChange {
+    fn from(value: ListenerInput<Pointer<Down>>) -> Self {
+        Self(value.target)
+    }
+}
+
+pub struct TillPlugin;
+
+impl Plugin for TillPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TakeChange>()
+            .init_resource::<Till>()
+            .add_systems(OnEnter(GameState::MainMenu), spawn_till)
+            .add_systems(
+                Update,
+                (
+                    take_change.run_if(in_state(CustomerState::Coins)),
+                    till_stacks.run_if(resource_changed::<Till>()),
+                ),
+            )
+            .add_systems(OnEnter(GameState::DayStart), |mut till: ResMut<Till>| {
+                *till = Till::default()
+            })
+            .add_systems(
+                OnEnter(GameState::DayEnd),
+                count_till.before(super::accounting),
+            );
+    }
+}
+
+fn spawn_till(
+    mut cmd: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let width = SLOT_SPACING * DENOMINATIONS.len() as f32;
+    cmd.spawn(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Box::new(
+            width,
+            TRAY_HEIGHT,
+            SLOT_SPACING,
+        ))),
+        material: materials.add(Color::rgb(0.4, 0.25, 0.1).into()),
+        transform: Transform::from_translation(
+            TILL_POS + Vec3::new((width - SLOT_SPACING) / 2.0, 0.0, 0.0),
+        ),
+        ..default()
+    });
+
+    let material = materials.add(Color::GOLD.into());
+    for (i, face) in DENOMINATIONS.iter().enumerate() {
+        cmd.spawn((
+            PbrBundle {
+                mesh: meshes.add(coins::coin_mesh(*face)),
+                material: material.clone(),
+                transform: Transform::from_translation(
+                    TILL_POS + Vec3::new(SLOT_SPACING * i as f32, 0.0, 0.0),
+                )
+                .with_scale(Vec3::ZERO),
+                ..default()
+            },
+            TillSlot(i),
+            TooltipBundle::new(""),
+            On::<Pointer<Down>>::send_event::<TakeChange>(),
+        ));
+    }
+}
+
+fn till_stacks(mut q: Query<(&mut Transform, &mut TooltipText, &TillSlot)>, till: Res<Till>) {
+    for (mut tr, mut txt, TillSlot(i)) in q.iter_mut() {
+        let n = till.0[*i];
+
+        // stacks grow upwards, one layer per coin
+        let layers = n.min(MAX_STACK) as f32;
+        tr.scale = if n > 0 {
+            Vec3::new(1.0, layers, 1.0)
+        } else {
+            Vec3::ZERO
+        };
+        tr.translation.y = TILL_POS.y + TRAY_HEIGHT / 2.0 + coins::COIN_HEIGHT * layers / 2.0;
+
+        txt.0 = format!("{n} x {:.0} gold", DENOMINATIONS[*i]);
+    }
+}
+
+fn take_change(
+    mut cmd: Commands,
+    mut er: EventReader<TakeChange>,
+    slots: Query<&TillSlot>,
+    change: Query<&Coin, With<coins::Change>>,
+    mut till: ResMut<Till>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut given = change.iter().count();
+
+    for TakeChange(ent) in er.read() {
+        let Ok(TillSlot(i)) = slots.get(*ent) else {
+            continue;
+        };
+        let Some(face) = till.take(*i) else {
+            continue;
+        };
+
+        coins::spawn_change(&mut cmd, &mut meshes, &mut materials, face, given);
+        given += 1;
+    }
+}
+
+/// Counts the till against the day's sales, whatever doesn't add up is put in the books
+fn count_till(
+    till: Res<Till>,
+    gold: Res<DailyGold>,
+    stolen: Res<Stolen>,
+    mut ledger: ResMut<Ledger>,
+) {
+    // coins thieves made off with are already on the books as theft
+    let short = Till::float() + **gold - stolen.coins - till.total();
+
+    if short > 0.0 {
+        ledger.push(("Till short", short));
+    } else if short < 0.0 {
+        ledger.push(("Till over", short));
+    }
+}
@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::save::{SaveData, Townsperson};
+
+use super::customer::CustomerState;
+
+const NAMES: &[&str] = &[
+    "Agnes", "Albert", "Ada", "Bertram", "Beatrix", "Cedric", "Clara", "Dorian", "Edith", "Elias",
+    "Fern", "Godfrey", "Greta", "Hugo", "Ida", "Jasper", "Juniper", "Linus", "Mabel", "Magnus",
+    "Nell", "Oswald", "Petra", "Rowan", "Rosalind", "Silas", "Tilda", "Ulric", "Wilma", "Yorick",
+];
+/// Most people the roster keeps track of before it stops adding new faces
+const MAX_ROSTER: usize = 40;
+/// Odds of someone already on the roster coming back rather than a new face
+const RETURN_ODDS: f64 = 0.6;
+/// Sales someone needs before they become a regular
+const LOYAL_AFTER: u32 = 3;
+/// Times someone caught the player cheating before they won't buy from the stall
+const REFUSE_AFTER: u32 = 2;
+
+/// How someone feels about the stall, going by their past visits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attitude {
+    New,
+    Returning,
+    /// Never been cheated and keeps coming back, doesn't watch so closely
+    Loyal,
+    /// Caught the player cheating before, watches like a hawk
+    Wary,
+    /// Caught the player cheating too often, won't buy anything
+    Refusing,
+}
+
+impl Attitude {
+    pub fn of(person: &Townsperson) -> Self {
+        if person.cheated >= REFUSE_AFTER {
+            Self::Refusing
+        } else if person.cheated > 0 {
+            Self::Wary
+        } else if person.served >= LOYAL_AFTER {
+            Self::Loyal
+        } else if person.visits > 1 {
+            Self::Returning
+        } else {
+            Self::New
+        }
+    }
+
+    /// Scales the odds of looking away from the scale
+    pub fn distraction(&self) -> f32 {
+        match self {
+            Self::Loyal => 1.5,
+            Self::Wary => 0.5,
+            _ => 1.0,
+        }
+    }
+
+    /// Scales how far off an order can be before they notice
+    pub fn threshold(&self) -> f32 {
+        match self {
+            Self::Wary => 0.5,
+            _ => 1.0,
+        }
+    }
+}
+
+/// The townsperson a customer is, by their place on the roster
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Visitor(usize);
+
+impl Visitor {
+    pub fn attitude(&self, save: &SaveData) -> Attitude {
+        save.roster
+            .get(self.0)
+            .map(Attitude::of)
+            .unwrap_or(Attitude::New)
+    }
+
+    /// What they say on reaching the stall, in place of the usual greeting
+    pub fn greeting(&self, save: &SaveData, usual: &[String]) -> Vec<String> {
+        let Some(person) = save.roster.get(self.0) else {
+            return usual.to_vec();
+        };
+        let name = &person.name;
+
+        let intro = match Attitude::of(person) {
+            Attitude::New => format!("I'm {name}, I don't think we've met."),
+            Attitude::Returning => format!("{name} again."),
+            Attitude::Loyal => format!("It's {name}! I wouldn't shop anywhere else."),
+            Attitude::Wary => {
+                format!("{name}. I remember you, I'll be keeping an eye on that scale.")
+            }
+            Attitude::Refusing => {
+                return vec![format!(
+                    "{name}. After last time? I'm not buying anything from a cheat."
+                )]
+            }
+        };
+        std::iter::once(intro)
+            .chain(usual.iter().cloned())
+            .collect()
+    }
+}
+
+/// Pick who comes to the stall as `archetype`, adding someone new to the roster if there's room
+pub fn visitor(save: &mut SaveData, archetype: &str, rng: &mut impl Rng) -> Option<Visitor> {
+    let known: Vec<usize> = save
+        .roster
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.archetype == archetype)
+        .map(|(i, _)| i)
+        .collect();
+
+    let full = save.roster.len() >= MAX_ROSTER;
+    let index = match known.choose(rng) {
+        Some(&i) if full || rng.gen_bool(RETURN_ODDS) => i,
+        _ if full => return None,
+        _ => {
+            let name = new_name(&save.roster, rng);
+            save.roster.push(Townsperson {
+                name,
+                archetype: archetype.to_string(),
+                visits: 0,
+                served: 0,
+                cheated: 0,
+            });
+            save.roster.len() - 1
+        }
+    };
+    save.roster[index].visits += 1;
+
+    Some(Visitor(index))
+}
+
+fn new_name(roster: &[Townsperson], rng: &mut impl Rng) -> String {
+    let unused: Vec<&str> = NAMES
+        .iter()
+        .copied()
+        .filter(|n| !roster.iter().any(|p| p.name == *n))
+        .collect();
+
+    match unused.choose(rng) {
+        Some(name) => name.to_string(),
+        // everyone's been named, so it's their kids coming in now
+        None => {
+            let parent = NAMES.choose(rng).expect("No names");
+            (1..)
+                .map(|n| match n {
+                    1 => format!("{parent} the Younger"),
+                    n => format!("{parent} the Younger {n}"),
+                })
+                .find(|name| !roster.iter().any(|p| p.name == *name))
+                .expect("No unused names")
+        }
+    }
+}
+
+pub struct TownsfolkPlugin;
+
+impl Plugin for TownsfolkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(CustomerState::Payment), record_served)
+            .add_systems(OnEnter(CustomerState::Angry), record_cheated);
+    }
+}
+
+fn record_served(mut save: ResMut<SaveData>, q: Query<&Visitor>) {
+    for visitor in q.iter() {
+        if let Some(person) = save.roster.get_mut(visitor.0) {
+            person.served += 1;
+        }
+    }
+}
+
+fn record_cheated(mut save: ResMut<SaveData>, q: Query<&Visitor>) {
+    for visitor in q.iter() {
+        if let Some(person) = save.roster.get_mut(visitor.0) {
+            person.cheated += 1;
+        }
+    }
+}
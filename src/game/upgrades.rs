@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    assets::{GameData, UpgradeList},
+    save::SaveData,
+};
+
+use super::{GameState, Reputation, TotalExpenses, TotalGold};
+
+/// What an upgrade does once bought
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub enum Effect {
+    /// Grams scooped at a time while holding the modifier
+    FineScoop(f32),
+    /// Sus weights can sit on the scale next to their honest counterparts
+    StackSus,
+    /// Multiplies the time between customers
+    CustomerWait(f32),
+    /// Reputation every game starts out with on top of the usual
+    Reputation(u8),
+    /// Multiplies how likely customers are to get distracted
+    Distraction(f32),
+}
+
+/// Everything the bought upgrades add up to
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Perks {
+    pub fine_scoop: f32,
+    pub stack_sus: bool,
+    pub customer_wait: f32,
+    pub reputation: u8,
+    pub distraction: f32,
+}
+
+impl Default for Perks {
+    fn default() -> Self {
+        Self {
+            fine_scoop: 0.5,
+            stack_sus: false,
+            customer_wait: 1.0,
+            reputation: 0,
+            distraction: 1.0,
+        }
+    }
+}
+
+impl Perks {
+    fn apply(&mut self, effect: Effect) {
+        match effect {
+            Effect::FineScoop(amnt) => self.fine_scoop = self.fine_scoop.min(amnt),
+            Effect::StackSus => self.stack_sus = true,
+            Effect::CustomerWait(f) => self.customer_wait *= f,
+            Effect::Reputation(r) => self.reputation = self.reputation.saturating_add(r),
+            Effect::Distraction(f) => self.distraction *= f,
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone)]
+pub struct BuyUpgrade(pub String);
+
+pub struct UpgradesPlugin;
+
+impl Plugin for UpgradesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Perks>()
+            .add_event::<BuyUpgrade>()
+            .add_systems(OnExit(GameState::Loading), update_perks)
+            .add_systems(
+                Update,
+                (
+                    buy.run_if(in_state(GameState::Shop)),
+                    update_perks.run_if(
+                        resource_exists::<GameData>().and_then(resource_changed::<SaveData>()),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Gold left over to spend
+pub fn spendable(gold: &TotalGold, expenses: &TotalExpenses) -> f32 {
+    **gold - **expenses
+}
+
+fn buy(
+    mut er: EventReader<BuyUpgrade>,
+    mut save: ResMut<SaveData>,
+    mut gold: ResMut<TotalGold>,
+    mut rep: ResMut<Reputation>,
+    expenses: Res<TotalExpenses>,
+    data: Res<GameData>,
+    lists: Res<Assets<UpgradeList>>,
+) {
+    let Some(list) = lists.get(&data.upgrades) else {
+        error!("Upgrade list asset was missing");
+        return;
+    };
+
+    for BuyUpgrade(id) in er.read() {
+        let Some(upgrade) = list.get(id) else {
+            warn!("Tried to buy unknown upgrade {id}");
+            continue;
+        };
+
+        if save.owns(id) || spendable(&gold, &expenses) < upgrade.cost {
+            continue;
+        }
+
+        **gold -= upgrade.cost;
+        save.upgrades.push(id.clone());
+
+        // the sign goes up right away, not just next game
+        if let Effect::Reputation(r) = upgrade.effect {
+            **rep = rep.saturating_add(r).min(100);
+        }
+    }
+}
+
+fn update_perks(
+    mut perks: ResMut<Perks>,
+    save: Res<SaveData>,
+    data: Res<GameData>,
+    lists: Res<Assets<UpgradeList>>,
+) {
+    let Some(list) = lists.get(&data.upgrades) else {
+        return;
+    };
+
+    *perks = Perks::default();
+    for upgrade in save.upgrades.iter().filter_map(|id| list.get(id)) {
+        perks.apply(upgrade.effect);
+    }
+}
@@ -0,0 +1,197 @@
+use bevy::{pbr::NotShadowCaster, prelude::*};
+use bevy_mod_picking::prelude::*;
+use rand::prelude::*;
+
+use super::{GameRng, GameState, Turnout};
+
+/// Raindrops kept falling around the stall on a wet day
+const RAINDROPS: usize = 400;
+/// Where the rain falls, from one corner of the stall's surroundings to the other
+const RAIN_MIN: Vec3 = Vec3::new(-5.0, 0.0, -6.0);
+const RAIN_MAX: Vec3 = Vec3::new(5.0, 5.0, 2.5);
+const RAIN_SPEED: (f32, f32) = (6.0, 9.0);
+/// How far a drop is blown sideways for each unit it falls
+const RAIN_SLANT: f32 = 0.15;
+
+/// The sky over the market today
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Overcast,
+    Rain,
+    Wind,
+}
+
+impl Weather {
+    pub const ALL: [Weather; 4] = [Self::Clear, Self::Overcast, Self::Rain, Self::Wind];
+
+    /// How often each kind of day comes around
+    const fn odds(&self) -> f32 {
+        match self {
+            Self::Clear => 5.0,
+            Self::Overcast => 3.0,
+            Self::Rain => 2.0,
+            Self::Wind => 2.0,
+        }
+    }
+
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Clear => "Clear skies",
+            Self::Overcast => "Overcast",
+            Self::Rain => "Rain",
+            Self::Wind => "Windy",
+        }
+    }
+
+    /// A line for the paper about what to expect
+    pub const fn forecast(&self) -> &'static str {
+        match self {
+            Self::Clear => "Fine weather for trading.",
+            Self::Overcast => "Grey skies all day, folk will have their heads in the clouds.",
+            Self::Rain => "Showers expected, fewer shoppers will brave the market.",
+            Self::Wind => "Strong gusts, hold on to your scales.",
+        }
+    }
+
+    /// How much of the daylight makes it through the clouds
+    pub const fn daylight(&self) -> f32 {
+        match self {
+            Self::Clear | Self::Wind => 1.0,
+            Self::Overcast => 0.55,
+            Self::Rain => 0.4,
+        }
+    }
+
+    /// Fog thickened by the weather, on top of the time of day
+    pub const fn haze(&self) -> f32 {
+        match self {
+            Self::Clear | Self::Wind => 0.0,
+            Self::Overcast => 0.01,
+            Self::Rain => 0.03,
+        }
+    }
+
+    /// Multiplies how many people are out and about
+    pub const fn turnout(&self) -> f32 {
+        match self {
+            Self::Rain => 0.6,
+            _ => 1.0,
+        }
+    }
+
+    /// Multiplies how likely customers are to lose track of the scales
+    pub const fn distraction(&self) -> f32 {
+        match self {
+            Self::Overcast => 1.5,
+            _ => 1.0,
+        }
+    }
+
+    /// Largest tilt the wind gives the scale beam, in degrees
+    pub const fn gusts(&self) -> f32 {
+        match self {
+            Self::Wind => 4.0,
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+struct Raindrop(f32);
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weather>()
+            .add_systems(
+                Update,
+                (
+                    make_it_rain.run_if(resource_changed::<Weather>()),
+                    fall.run_if(resource_equals(Weather::Rain)),
+                ),
+            )
+            .add_systems(OnEnter(GameState::Reset), |mut weather: ResMut<Weather>| {
+                *weather = Weather::default()
+            });
+    }
+}
+
+pub(super) fn roll_weather(
+    mut rng: ResMut<GameRng>,
+    mut weather: ResMut<Weather>,
+    mut turnout: ResMut<Turnout>,
+) {
+    *weather = *Weather::ALL
+        .choose_weighted(&mut **rng, Weather::odds)
+        .expect("No weather");
+    **turnout *= weather.turnout();
+    info!("Today's weather: {}", weather.label());
+}
+
+fn random_spot(rng: &mut impl Rng) -> Vec3 {
+    Vec3::new(
+        rng.gen_range(RAIN_MIN.x..RAIN_MAX.x),
+        rng.gen_range(RAIN_MIN.y..RAIN_MAX.y),
+        rng.gen_range(RAIN_MIN.z..RAIN_MAX.z),
+    )
+}
+
+/// Clears away yesterday's rain and starts today's, if there is any
+fn make_it_rain(
+    mut cmd: Commands,
+    weather: Res<Weather>,
+    drops: Query<Entity, With<Raindrop>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for ent in drops.iter() {
+        cmd.entity(ent).despawn_recursive();
+    }
+    if *weather != Weather::Rain {
+        return;
+    }
+
+    let mesh = meshes.add(shape::Box::new(0.01, 0.2, 0.01).into());
+    let material = materials.add(StandardMaterial {
+        base_color: Color::rgba(0.7, 0.75, 0.85, 0.5),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+
+    // drops aren't tied to the run, they only have to look like rain
+    let mut rng = SmallRng::from_entropy();
+    for _ in 0..RAINDROPS {
+        cmd.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(random_spot(&mut rng))
+                    .with_rotation(Quat::from_rotation_z(-RAIN_SLANT)),
+                ..default()
+            },
+            NotShadowCaster,
+            // clicks go straight through to the stall
+            Pickable::IGNORE,
+            Raindrop(rng.gen_range(RAIN_SPEED.0..RAIN_SPEED.1)),
+        ));
+    }
+}
+
+fn fall(mut q: Query<(&mut Transform, &Raindrop)>, time: Res<Time>) {
+    let mut rng = SmallRng::from_entropy();
+    for (mut tr, Raindrop(speed)) in q.iter_mut() {
+        let drop = speed * time.delta_seconds();
+        tr.translation.y -= drop;
+        tr.translation.x -= drop * RAIN_SLANT;
+
+        // back up into the clouds once it hits the ground
+        if tr.translation.y < RAIN_MIN.y || tr.translation.x < RAIN_MIN.x {
+            let spot = random_spot(&mut rng);
+            tr.translation = Vec3::new(spot.x, RAIN_MAX.y, spot.z);
+        }
+    }
+}
@@ -14,6 +14,10 @@ pub struct CursorPos(Vec2);
 pub enum Action {
     Advance,
     Mod,
+    Undo,
+    Redo,
+    Clear,
+    Notes,
 }
 
 pub struct InputPlugin;
@@ -26,6 +30,10 @@ impl Plugin for InputPlugin {
                 (InputKind::Keyboard(KeyCode::Space), Action::Advance),
                 (InputKind::Mouse(MouseButton::Left), Action::Advance),
                 (InputKind::Keyboard(KeyCode::ShiftLeft), Action::Mod),
+                (InputKind::Keyboard(KeyCode::Z), Action::Undo),
+                (InputKind::Keyboard(KeyCode::Y), Action::Redo),
+                (InputKind::Keyboard(KeyCode::Back), Action::Clear),
+                (InputKind::Keyboard(KeyCode::N), Action::Notes),
             ]))
             .add_plugins(InputManagerPlugin::<Action>::default())
             .add_systems(PreUpdate, update_cursor_pos);
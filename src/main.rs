@@ -9,6 +9,7 @@ use crowd::CrowdPlugin;
 use game::{GamePlugin, GameState};
 use input::InputPlugin;
 use player::PlayerPlugin;
+use save::SavePlugin;
 use sound::SoundPlugin;
 use ui::{UiPlugin};
 use utils::{despawn_all, every, UtilPlugin};
@@ -19,6 +20,7 @@ mod crowd;
 mod game;
 mod input;
 mod player;
+mod save;
 mod sound;
 mod ui;
 mod utils;
@@ -71,6 +73,7 @@ fn main() {
             PlayerPlugin,
             UiPlugin,
             SoundPlugin,
+            SavePlugin,
         ))
         .add_systems(OnEnter(AppState::Done), setup)
         .add_systems(
@@ -1,12 +1,17 @@
 use std::time::Duration;
 
 use bevy::core_pipeline::fxaa::Fxaa;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_tweening::{
     component_animator_system, AnimationSystem, Animator, EaseFunction, Lens, Tween,
 };
 
-use crate::game::{ScaleContents, TargetWeight};
+use crate::assets::{GameData, RecipeBook};
+use crate::game::{
+    AgreedPrice, CounterContents, CustomerState, PriceBoard, ScaleContents, TargetProduct,
+    TargetWeight,
+};
 
 use crate::input::CursorPos;
 use crate::utils::CalcCost;
@@ -39,6 +44,30 @@ pub struct CustText;
 #[derive(Component, Debug)]
 pub struct ProfitText;
 
+/// Everything the current order costs the stall
+#[derive(SystemParam)]
+struct OrderCost<'w> {
+    contents: Res<'w, ScaleContents>,
+    counter: Res<'w, CounterContents>,
+    product: Res<'w, TargetProduct>,
+    // not there until everything has loaded
+    data: Option<Res<'w, GameData>>,
+    books: Res<'w, Assets<RecipeBook>>,
+}
+
+impl OrderCost<'_> {
+    /// Goods on the scale and the counter, and what went into the products they asked for
+    fn total(&self) -> f32 {
+        let product = self
+            .data
+            .as_ref()
+            .and_then(|d| self.books.get(&d.recipes))
+            .and_then(|b| b.get(&self.product.name))
+            .map_or(0.0, |r| r.ingredients.cost() * self.product.count as f32);
+        self.contents.cost() + self.counter.given.cost() + product
+    }
+}
+
 pub const DEFAULT_LOOK: Vec3 = Vec3::new(0.0, 1.75, 0.0);
 pub const DEADZONE: f32 = (WINDOW_SIZE.y / 2.0) * 0.66;
 pub const LOOK_AMOUNT: f32 = 0.1;
@@ -56,12 +85,31 @@ impl Plugin for PlayerPlugin {
         .add_systems(
             Update,
             (
-                update_cost_text.run_if(resource_changed::<ScaleContents>()),
-                update_customer_text.run_if(resource_changed::<TargetWeight>()),
+                update_cost_text.run_if(
+                    resource_changed::<ScaleContents>()
+                        .or_else(resource_changed::<CounterContents>())
+                        .or_else(resource_changed::<TargetProduct>()),
+                ),
+                update_customer_text.run_if(
+                    resource_changed::<TargetWeight>()
+                        .or_else(resource_changed::<TargetProduct>())
+                        .or_else(resource_changed::<AgreedPrice>())
+                        .or_else(resource_changed::<PriceBoard>()),
+                ),
                 update_profit_text.run_if(
-                    resource_changed::<ScaleContents>().or_else(resource_changed::<TargetWeight>()),
+                    resource_changed::<ScaleContents>()
+                        .or_else(resource_changed::<CounterContents>())
+                        .or_else(resource_changed::<TargetWeight>())
+                        .or_else(resource_changed::<TargetProduct>())
+                        .or_else(resource_changed::<AgreedPrice>())
+                        .or_else(resource_changed::<PriceBoard>()),
                 ),
             ),
+        )
+        // a new order starts from a clean slate
+        .add_systems(
+            OnEnter(CustomerState::Measuring),
+            (update_cost_text, update_profit_text),
         );
     }
 }
@@ -91,75 +139,6 @@ fn spawn_player(mut cmd: Commands) {
         Player,
         LookTarget(DEFAULT_LOOK),
     ));
-
-    // UI stuff
-    // cmd.spawn(NodeBundle {
-    //     style: Style {
-    //         position_type: PositionType::Absolute,
-    //         right: Val::Px(10.0),
-    //         bottom: Val::Px(10.0),
-    //         display: Display::Grid,
-    //         grid_template_columns: vec![GridTrack::auto(), GridTrack::fr(1.0)],
-    //         padding: UiRect::all(Val::Px(4.0)),
-    //         border: UiRect::all(Val::Px(2.0)),
-    //         ..default()
-    //     },
-    //     border_color: Color::BLACK.into(),
-    //     background_color: Color::rgb_u8(255, 87, 51).into(),
-    //     ..default()
-    // })
-    // .with_children(|parent| {
-    //     parent.spawn(TextBundle::from_section(
-    //         "Customer will pay: ",
-    //         TextStyle::default(),
-    //     ));
-
-    //     parent.spawn((
-    //         TextBundle {
-    //             style: Style {
-    //                 justify_self: JustifySelf::End,
-    //                 ..default()
-    //             },
-    //             text: Text::from_section("", TextStyle::default()),
-    //             ..default()
-    //         },
-    //         CustText,
-    //     ));
-
-    //     parent.spawn(TextBundle::from_section(
-    //         "Total cost: ",
-    //         TextStyle::default(),
-    //     ));
-
-    //     parent.spawn((
-    //         TextBundle {
-    //             style: Style {
-    //                 justify_self: JustifySelf::End,
-    //                 ..default()
-    //             },
-    //             text: Text::from_section("", TextStyle::default()),
-    //             ..default()
-    //         },
-    //         CostText,
-    //     ));
-
-    //     parent.spawn(TextBundle::from_section(
-    //         "Total profit: ",
-    //         TextStyle::default(),
-    //     ));
-
-    //     parent.spawn((
-    //         TextBundle {
-    //             style: Style {
-    //                 justify_self: JustifySelf::End,
-    //                 ..default()
-    //             },
-    //             text: Text::from_section("", TextStyle::default()),
-    //             ..default()
-    //         },
-    //         ProfitText,
-    //     ));
-    // });
 }
 
 fn look(mut q: Query<(&mut Transform, &LookTarget), (With<Player>, Changed<LookTarget>)>) {
@@ -207,30 +186,38 @@ fn tilt_camera_toward_mouse(
     }
 }
 
-fn update_cost_text(mut q: Query<&mut Text, With<CostText>>, contents: Res<ScaleContents>) {
+fn update_cost_text(mut q: Query<&mut Text, With<CostText>>, order: OrderCost) {
     for mut text in q.iter_mut() {
         let num_section = &mut text.sections[0];
-        let cost: f32 = contents.cost();
+        let cost: f32 = order.total();
         num_section.value = format!("{cost:.0} gold");
     }
 }
 
-fn update_customer_text(mut q: Query<&mut Text, With<CustText>>, target: Res<TargetWeight>) {
+fn update_customer_text(
+    mut q: Query<&mut Text, With<CustText>>,
+    target: Res<TargetWeight>,
+    product: Res<TargetProduct>,
+    agreed: Res<AgreedPrice>,
+    board: Res<PriceBoard>,
+) {
     for mut text in q.iter_mut() {
         let num_section = &mut text.sections[0];
-        let cost: f32 = target.customer_cost();
+        let cost: f32 = agreed.or_list(&target, &product, &board);
         num_section.value = format!("{cost:.0} gold");
     }
 }
 
 fn update_profit_text(
     mut q: Query<&mut Text, With<ProfitText>>,
-    contents: Res<ScaleContents>,
+    order: OrderCost,
     target: Res<TargetWeight>,
+    agreed: Res<AgreedPrice>,
+    board: Res<PriceBoard>,
 ) {
     for mut text in q.iter_mut() {
         let num_section = &mut text.sections[0];
-        let profit = target.customer_cost() - contents.cost();
+        let profit = agreed.or_list(&target, &order.product, &board) - order.total();
         num_section.value = format!("{profit:.0} gold");
     }
 }
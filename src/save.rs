@@ -0,0 +1,123 @@
+//! Progress that outlives a single game
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::GameState;
+
+/// Where the save file lives, next to wherever the game was started from
+#[cfg(not(target_family = "wasm"))]
+const SAVE_PATH: &str = "save.ron";
+
+/// How many endless runs are kept on the high score table
+const MAX_HIGH_SCORES: usize = 10;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: f32,
+    pub profit: f32,
+    pub days: usize,
+}
+
+/// Someone from the village and how their visits to the stall went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Townsperson {
+    pub name: String,
+    /// Name of the character file they act like
+    pub archetype: String,
+    pub visits: u32,
+    /// Visits that ended with them paying
+    pub served: u32,
+    /// Visits that ended with them catching the player cheating
+    pub cheated: u32,
+}
+
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    /// Ids of every upgrade bought so far
+    pub upgrades: Vec<String>,
+    /// Campaign week to pick back up from
+    pub campaign_week: usize,
+    /// Best endless runs, highest first
+    pub high_scores: Vec<HighScore>,
+    /// Everyone who has visited the stall
+    pub roster: Vec<Townsperson>,
+}
+
+impl SaveData {
+    #[cfg(not(target_family = "wasm"))]
+    fn load() -> Self {
+        let Ok(raw) = std::fs::read_to_string(SAVE_PATH) else {
+            info!("No save file found, starting fresh");
+            return Self::default();
+        };
+
+        ron::from_str(&raw).unwrap_or_else(|e| {
+            error!("Couldn't read save file: {e}");
+            Self::default()
+        })
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn store(&self) {
+        let raw = match ron::ser::to_string_pretty(self, default()) {
+            Ok(raw) => raw,
+            Err(e) => {
+                error!("Couldn't serialize save data: {e}");
+                return;
+            }
+        };
+
+        if let Err(e) = std::fs::write(SAVE_PATH, raw) {
+            error!("Couldn't write save file: {e}");
+        }
+    }
+
+    // there's nowhere to keep a file on the web
+    #[cfg(target_family = "wasm")]
+    fn load() -> Self {
+        Self::default()
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn store(&self) {}
+
+    pub fn owns(&self, upgrade: &str) -> bool {
+        self.upgrades.iter().any(|u| u == upgrade)
+    }
+
+    pub fn add_score(&mut self, score: HighScore) {
+        self.high_scores.push(score);
+        self.high_scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+        self.high_scores.truncate(MAX_HIGH_SCORES);
+    }
+}
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveData::load()).add_systems(
+            Update,
+            // the roster changes with every customer, so it's all written once the stall closes
+            write_save.run_if(not(stall_open).and_then(resource_changed::<SaveData>())),
+        );
+    }
+}
+
+fn stall_open(state: Res<State<GameState>>) -> bool {
+    matches!(
+        state.get(),
+        GameState::DayStart | GameState::Waiting | GameState::Customer
+    )
+}
+
+fn write_save(save: Res<SaveData>) {
+    // nothing new to write when it was just loaded
+    if save.is_added() {
+        return;
+    }
+
+    save.store();
+}
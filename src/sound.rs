@@ -1,5 +1,5 @@
 use bevy::{audio::VolumeLevel, prelude::*};
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rand::{thread_rng, Rng};
 
 use crate::{
     assets::Sounds,
@@ -55,7 +55,7 @@ fn sfx_add_item(
     mut events: EventReader<AddItem>,
     type_q: Query<&ItemType>,
 ) {
-    let mut rng = SmallRng::from_entropy();
+    let mut rng = thread_rng();
     for event in events.read() {
         let Ok(t) = type_q.get(**event) else {
             continue;
@@ -69,7 +69,7 @@ fn sfx_add_item(
                     ..default()
                 });
             }
-            ItemType::SpiderEyes => {
+            ItemType::SpiderEyes | ItemType::Eggs | ItemType::Candles => {
                 cmd.spawn(AudioBundle {
                     source: sounds.scoop_hard.clone(),
                     settings: PlaybackSettings::DESPAWN,
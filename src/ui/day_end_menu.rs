@@ -5,7 +5,10 @@ use bevy_tweening::*;
 use leafwing_input_manager::action_state::ActionState;
 
 use crate::{
-    game::{DailyExpenses, DailyGold, GameState},
+    game::{
+        CurrentWeek, DailyExpenses, DailyGold, DayIndex, Footfall, GameOutcome, GameState, Ledger,
+        RepLog, Strikes,
+    },
     utils::{
         despawn_all,
         lenses::{BackgroundColorLens, TextLens},
@@ -66,12 +69,17 @@ pub fn spawn_menu(mut cmd: Commands, mut state: ResMut<NextState<MenuState>>) {
 
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_modal(
     mut cmd: Commands,
     mut er: EventReader<TweenCompleted>,
     fonts: Res<Fonts>,
     income: Res<DailyGold>,
     expenses: Res<DailyExpenses>,
+    ledger: Res<Ledger>,
+    strikes: Res<Strikes>,
+    rep_log: Res<RepLog>,
+    footfall: Res<Footfall>,
     mut state: ResMut<NextState<MenuState>>
 ) {
     for event in er.read() {
@@ -191,6 +199,57 @@ pub fn spawn_modal(
                                         .with_delay(Duration::from_millis(200)),
                                     ),
                                 ));
+
+                                for (name, amnt) in ledger.iter() {
+                                    parent.spawn(TextBundle::from_section(
+                                        format!("{name}: "),
+                                        TextStyle {
+                                            font: handwriting.clone(),
+                                            font_size: 20.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ));
+
+                                    parent.spawn(TextBundle::from_sections(vec![
+                                        // a till that counts over is money in, not out
+                                        TextSection::new(
+                                            format!("{:+.0}", -amnt),
+                                            TextStyle {
+                                                font: font.clone(),
+                                                font_size: 20.0,
+                                                color: if *amnt < 0.0 { Color::DARK_GREEN } else { Color::RED },
+                                            },
+                                        ),
+                                        TextSection::new(
+                                            " gold",
+                                            TextStyle {
+                                                font: font.clone(),
+                                                font_size: 20.0,
+                                                color: Color::BLACK,
+                                            },
+                                        ),
+                                    ]));
+                                }
+
+                                if **strikes > 0 {
+                                    parent.spawn(TextBundle::from_section(
+                                        "Strikes: ",
+                                        TextStyle {
+                                            font: handwriting.clone(),
+                                            font_size: 20.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ));
+
+                                    parent.spawn(TextBundle::from_section(
+                                        strikes.to_string(),
+                                        TextStyle {
+                                            font: font.clone(),
+                                            font_size: 20.0,
+                                            color: Color::RED,
+                                        },
+                                    ));
+                                }
                             });
 
                         parent.spawn(NodeBundle {
@@ -249,12 +308,82 @@ pub fn spawn_modal(
                                         Tween::new(
                                             EaseMethod::Linear,
                                             Duration::from_millis(200),
-                                            TextLens::new((**income - **expenses).to_string()),
+                                            TextLens::new((**income - **expenses - ledger.total()).to_string()),
                                         )
                                         .with_delay(Duration::from_millis(400)),
                                     ),
                                 ));
                             });
+
+                        parent.spawn(NodeBundle {
+                            style: Style {
+                                height: Val::Px(3.0),
+                                width: Val::Percent(75.0),
+                                margin: UiRect::vertical(Val::Px(5.0)),
+                                ..default()
+                            },
+                            background_color: Color::BLACK.into(),
+                            ..default()
+                        });
+
+                        parent.spawn(TextBundle::from_section(
+                            "Reputation",
+                            TextStyle {
+                                font: handwriting.clone(),
+                                font_size: 24.0,
+                                color: Color::BLACK,
+                            },
+                        ));
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    display: Display::Grid,
+                                    grid_template_columns: vec![
+                                        GridTrack::auto(),
+                                        GridTrack::fr(1.0),
+                                    ],
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                for (reason, delta) in rep_log.iter() {
+                                    parent.spawn(TextBundle::from_section(
+                                        format!("{reason}: "),
+                                        TextStyle {
+                                            font: handwriting.clone(),
+                                            font_size: 20.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ));
+
+                                    parent.spawn(TextBundle::from_section(
+                                        format!("{delta:+}"),
+                                        TextStyle {
+                                            font: font.clone(),
+                                            font_size: 20.0,
+                                            color: if *delta > 0 { Color::DARK_GREEN } else { Color::RED },
+                                        },
+                                    ));
+                                }
+                            });
+
+                        // word of mouth, from how the customers left today
+                        let word = if **footfall < 1.0 {
+                            "Word is getting around, expect a quieter day tomorrow"
+                        } else if **footfall > 1.0 {
+                            "Customers are telling their friends, expect a busier day tomorrow"
+                        } else {
+                            "Nobody's talking about the stall, tomorrow should be the same as ever"
+                        };
+                        parent.spawn(TextBundle::from_section(
+                            word,
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 18.0,
+                                color: Color::BLACK,
+                            },
+                        ));
                     });
             });
         }
@@ -263,9 +392,16 @@ pub fn spawn_modal(
     state.set(MenuState::Done)
 }
 
-fn advance(actions: Res<ActionState<Action>>, mut state: ResMut<NextState<GameState>>, mut menu_state: ResMut<NextState<MenuState>>) {
+fn advance(actions: Res<ActionState<Action>>, day: Res<DayIndex>, week: CurrentWeek, outcome: Res<GameOutcome>, mut state: ResMut<NextState<GameState>>, mut menu_state: ResMut<NextState<MenuState>>) {
     if actions.just_pressed(Action::Advance) {
-        state.set(GameState::DayStart);
+        // no point shopping once the game is over
+        if *outcome != GameOutcome::Survived {
+            state.set(GameState::DayStart);
+        } else if week.is_over(&day) {
+            state.set(GameState::WeekEnd);
+        } else {
+            state.set(GameState::Shop);
+        }
         menu_state.set(MenuState::None);
     }
 }
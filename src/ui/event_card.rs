@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    assets::Fonts,
+    game::{Announcement, DayIndex},
+    utils::despawn_all,
+};
+
+use super::{NeedsTextSet, PARCHMENT};
+
+#[derive(Component)]
+struct Card;
+
+pub struct EventCardPlugin;
+
+impl Plugin for EventCardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_card.run_if(resource_added::<Announcement>()),
+                despawn_all::<Card>.run_if(resource_removed::<Announcement>()),
+            )
+                .in_set(NeedsTextSet),
+        );
+    }
+}
+
+fn spawn_card(mut cmd: Commands, fonts: Res<Fonts>, news: Res<Announcement>, day: Res<DayIndex>) {
+    let masthead = TextStyle {
+        font: fonts.handwritten.clone(),
+        font_size: 32.0,
+        color: Color::BLACK,
+    };
+    let headline = TextStyle {
+        font: fonts.handwritten.clone(),
+        font_size: 24.0,
+        color: Color::BLACK,
+    };
+    let body = TextStyle {
+        font: fonts.default.clone(),
+        font_size: 18.0,
+        color: Color::BLACK,
+    };
+
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        },
+        Card,
+    ))
+    .with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(15.0)),
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)),
+                    row_gap: Val::Px(6.0),
+                    width: Val::Percent(50.0),
+                    ..default()
+                },
+                border_color: Color::BLACK.into(),
+                background_color: PARCHMENT.into(),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section("The Market Crier", masthead));
+                parent.spawn(TextBundle::from_section(
+                    format!("Day {} - {}", **day + 1, news.weather.label()),
+                    body.clone(),
+                ));
+
+                // horizontal rule
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        height: Val::Px(3.0),
+                        width: Val::Percent(90.0),
+                        margin: UiRect::vertical(Val::Px(5.0)),
+                        ..default()
+                    },
+                    background_color: Color::BLACK.into(),
+                    ..default()
+                });
+
+                parent.spawn(
+                    TextBundle::from_section(news.headline.clone(), headline)
+                        .with_text_alignment(TextAlignment::Center),
+                );
+                parent.spawn(
+                    TextBundle::from_section(news.story.clone(), body.clone())
+                        .with_text_alignment(TextAlignment::Center),
+                );
+                parent.spawn(
+                    TextBundle::from_section(
+                        format!("Weather: {}", news.weather.forecast()),
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::DARK_GRAY,
+                            ..body.clone()
+                        },
+                    )
+                    .with_text_alignment(TextAlignment::Center),
+                );
+
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                border: UiRect::all(Val::Px(2.0)),
+                                width: Val::Auto,
+                                padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                margin: UiRect::top(Val::Px(15.0)),
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            },
+                            border_color: Color::BLACK.into(),
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        On::<Pointer<Down>>::run(|mut cmd: Commands| {
+                            cmd.remove_resource::<Announcement>()
+                        }),
+                        On::<Pointer<Over>>::listener_insert(BackgroundColor(Color::ALICE_BLUE)),
+                        On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "Open the stall",
+                                TextStyle {
+                                    font_size: 24.0,
+                                    ..body.clone()
+                                },
+                            ),
+                            Pickable::IGNORE,
+                        ));
+                    });
+            });
+    });
+}
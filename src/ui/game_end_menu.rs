@@ -7,7 +7,11 @@ use bevy_tweening::*;
 
 use crate::{
     assets::Fonts,
-    game::{GameState, Reputation, TotalExpenses, TotalGold},
+    game::{
+        score, ActiveDifficulty, DaysSurvived, GameMode, GameOutcome, GameState, Reputation,
+        TotalExpenses, TotalGold,
+    },
+    save::SaveData,
     utils::{
         despawn_all,
         lenses::{BackgroundColorLens, TextLens},
@@ -57,6 +61,7 @@ fn spawn_menu(mut cmd: Commands) {
     ));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn finish_spawn(
     mut cmd: Commands,
     event: Listener<TweenDone>,
@@ -64,7 +69,52 @@ fn finish_spawn(
     income: Res<TotalGold>,
     expenses: Res<TotalExpenses>,
     reputation: Res<Reputation>,
+    outcome: Res<GameOutcome>,
+    mode: Res<GameMode>,
+    days: Res<DaysSurvived>,
+    difficulty: Res<ActiveDifficulty>,
+    save: Res<SaveData>,
 ) {
+    let (title, ending) = match *outcome {
+        GameOutcome::Survived => (
+            "Campaign Totals:",
+            format!(
+                "Ended the campaign with the village feeling {} towards your stand",
+                reputation.sentiment()
+            ),
+        ),
+        GameOutcome::Evicted => (
+            "Evicted!",
+            "You couldn't pay what you owed, and the market took your stall back".to_string(),
+        ),
+        GameOutcome::Missed => (
+            "Out of Business",
+            "You fell short of the week's goal and had to shut the stall".to_string(),
+        ),
+        GameOutcome::Arrested => (
+            "Arrested!",
+            "Your scales were seized and you were marched off to the stocks".to_string(),
+        ),
+    };
+
+    let ending = match *mode {
+        GameMode::Campaign => ending,
+        GameMode::Endless => format!(
+            "{ending}\n\nLasted {} days for a score of {:.0}",
+            **days,
+            score(&income, &expenses, &days)
+        ),
+    };
+    // only a campaign left part way through can be picked back up
+    let resumable = match *mode {
+        GameMode::Campaign => save.campaign_week,
+        GameMode::Endless => 0,
+    };
+    let play_label = match resumable {
+        0 => "Play again".to_string(),
+        week => format!("Continue from week {}", week + 1),
+    };
+
     cmd.entity(event.target).with_children(|parent| {
         parent
             .spawn(NodeBundle {
@@ -85,7 +135,7 @@ fn finish_spawn(
             .with_children(|parent| {
                 parent.spawn(TextBundle {
                     text: Text::from_section(
-                        "Weekly Totals:",
+                        title,
                         TextStyle {
                             font: fonts.handwritten.clone(),
                             font_size: 24.0,
@@ -94,6 +144,14 @@ fn finish_spawn(
                     ),
                     ..default()
                 });
+                parent.spawn(TextBundle::from_section(
+                    format!("Played on {}", difficulty.name),
+                    TextStyle {
+                        font: fonts.default.clone(),
+                        font_size: 16.0,
+                        color: Color::DARK_GRAY,
+                    },
+                ));
                 parent
                     .spawn(NodeBundle {
                         style: Style {
@@ -226,10 +284,7 @@ fn finish_spawn(
 
                 parent
                     .spawn((AnimatedTextBundle::from_seciton_with_delay(
-                        format!(
-                            "Ended the week with the village feeling {} towards your stand",
-                            reputation.sentiment()
-                        ),
+                        ending,
                         TextStyle {
                             font: fonts.handwritten.clone(),
                             font_size: 20.0,
@@ -267,7 +322,7 @@ fn finish_spawn(
                     .with_children(|parent| {
                         parent.spawn((
                             TextBundle::from_section(
-                                "Play again",
+                                play_label,
                                 TextStyle {
                                     font: fonts.default.clone(),
                                     font_size: 24.0,
@@ -278,6 +333,50 @@ fn finish_spawn(
                         ));
                     });
 
+                // a campaign left part way through can also be thrown away
+                if resumable > 0 {
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    border: UiRect::all(Val::Px(2.0)),
+                                    width: Val::Auto,
+                                    padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                    margin: UiRect::top(Val::Px(30.0)),
+                                    justify_content: JustifyContent::Center,
+                                    ..default()
+                                },
+                                border_color: Color::BLACK.into(),
+                                background_color: Color::NONE.into(),
+                                ..default()
+                            },
+                            On::<Pointer<Down>>::run(
+                                |mut save: ResMut<SaveData>,
+                                 mut state: ResMut<NextState<GameState>>| {
+                                    save.campaign_week = 0;
+                                    state.set(GameState::Reset)
+                                },
+                            ),
+                            On::<Pointer<Over>>::listener_insert(BackgroundColor(
+                                Color::ALICE_BLUE,
+                            )),
+                            On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    "Start over",
+                                    TextStyle {
+                                        font: fonts.default.clone(),
+                                        font_size: 24.0,
+                                        color: Color::BLACK,
+                                    },
+                                ),
+                                Pickable::IGNORE,
+                            ));
+                        });
+                }
+
                 #[cfg(not(target_family = "wasm"))]
                 parent
                     .spawn((
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    assets::Fonts,
+    game::{AcceptCounter, CustomerState, Haggle, Offer, PRICE_STEP},
+    utils::despawn_all,
+};
+
+use super::{NeedsTextSet, PARCHMENT};
+
+#[derive(Component)]
+struct Menu;
+
+#[derive(Component)]
+struct AskingText;
+
+#[derive(Component)]
+struct RemarkText;
+
+pub struct HaggleMenuPlugin;
+
+impl Plugin for HaggleMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(CustomerState::Haggle), spawn_menu)
+            .add_systems(
+                Update,
+                update_text
+                    .run_if(resource_exists_and_changed::<Haggle>())
+                    .in_set(NeedsTextSet),
+            )
+            .add_systems(OnExit(CustomerState::Haggle), despawn_all::<Menu>);
+    }
+}
+
+fn spawn_menu(mut cmd: Commands, fonts: Res<Fonts>) {
+    let heading = TextStyle {
+        font: fonts.handwritten.clone(),
+        font_size: 20.0,
+        color: Color::BLACK,
+    };
+    let body = TextStyle {
+        font: fonts.default.clone(),
+        font_size: 16.0,
+        color: Color::BLACK,
+    };
+
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                top: Val::Px(10.0),
+                width: Val::Percent(25.0),
+                border: UiRect::all(Val::Px(2.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            border_color: Color::BLACK.into(),
+            background_color: PARCHMENT.into(),
+            ..default()
+        },
+        Menu,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section("Name your price", heading.clone()));
+        parent.spawn((TextBundle::from_section("", body.clone()), RemarkText));
+
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                button(
+                    parent,
+                    "-",
+                    body.clone(),
+                    On::<Pointer<Down>>::run(|mut haggle: ResMut<Haggle>| {
+                        haggle.asking = (haggle.asking - PRICE_STEP).max(0.0);
+                    }),
+                );
+                parent.spawn((
+                    TextBundle::from_sections([
+                        TextSection::new("", heading.clone()),
+                        TextSection::new(" gold", heading.clone()),
+                    ]),
+                    AskingText,
+                ));
+                button(
+                    parent,
+                    "+",
+                    body.clone(),
+                    On::<Pointer<Down>>::run(|mut haggle: ResMut<Haggle>| {
+                        haggle.asking += PRICE_STEP;
+                    }),
+                );
+            });
+
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                button(
+                    parent,
+                    "Offer",
+                    body.clone(),
+                    On::<Pointer<Down>>::run(|mut ew: EventWriter<Offer>| ew.send_default()),
+                );
+                button(
+                    parent,
+                    "Take theirs",
+                    body.clone(),
+                    On::<Pointer<Down>>::run(|mut ew: EventWriter<AcceptCounter>| {
+                        ew.send_default()
+                    }),
+                );
+            });
+    });
+}
+
+fn button(parent: &mut ChildBuilder, label: &str, style: TextStyle, on_down: On<Pointer<Down>>) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    border: UiRect::all(Val::Px(2.0)),
+                    padding: UiRect::axes(Val::Px(10.), Val::Px(4.)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                border_color: Color::BLACK.into(),
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            on_down,
+            On::<Pointer<Over>>::listener_insert(BackgroundColor(Color::ALICE_BLUE)),
+            On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((TextBundle::from_section(label, style), Pickable::IGNORE));
+        });
+}
+
+fn update_text(
+    haggle: Res<Haggle>,
+    mut asking_q: Query<&mut Text, (With<AskingText>, Without<RemarkText>)>,
+    mut remark_q: Query<&mut Text, (With<RemarkText>, Without<AskingText>)>,
+) {
+    for mut text in asking_q.iter_mut() {
+        text.sections[0].value = format!("{:.0}", haggle.asking);
+    }
+
+    for mut text in remark_q.iter_mut() {
+        text.sections[0].value = format!("{} (usually {:.0} gold)", haggle.remark, haggle.list);
+    }
+}
@@ -0,0 +1,346 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use crate::{
+    assets::Fonts,
+    game::{DailyGold, DayTimer, GameState, Reputation},
+    input::Action,
+    player::{CostText, CustText, ProfitText},
+};
+
+use super::{NeedsTextSet, PARCHMENT};
+
+/// Hours the market is open, the sundial's face runs from one to the other
+const OPENING_HOUR: f32 = 7.0;
+const CLOSING_HOUR: f32 = 19.0;
+const DIAL_RADIUS: f32 = 50.0;
+const MARK_SIZE: Vec2 = Vec2::new(24.0, 14.0);
+const DIAL_WIDTH: f32 = DIAL_RADIUS * 2.0 + MARK_SIZE.x;
+const SHADOW_DOTS: usize = 6;
+const SHADOW_DOT: f32 = 4.0;
+const GAUGE_WIDTH: f32 = 120.0;
+
+/// Anything that's only on screen while the stall is open
+#[derive(Component, Debug)]
+struct Hud;
+
+#[derive(Component, Debug)]
+struct Notes;
+
+/// One dot of the gnomon's shadow, how far along it from the middle of the dial
+#[derive(Component, Debug)]
+struct Shadow(f32);
+
+#[derive(Component, Debug)]
+struct ClockText;
+
+#[derive(Component, Debug)]
+struct GoldText;
+
+#[derive(Component, Debug)]
+struct RepGauge;
+
+#[derive(Component, Debug)]
+struct RepText;
+
+/// Whether the merchant's notes are open on the counter
+#[derive(Resource, Debug, Clone, Copy, Default, Deref, DerefMut, PartialEq, Eq)]
+struct NotesOpen(bool);
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NotesOpen>()
+            .add_systems(OnExit(GameState::Loading), spawn_hud)
+            .add_systems(
+                Update,
+                (
+                    (|mut open: ResMut<NotesOpen>| **open = !**open)
+                        .run_if(action_just_pressed(Action::Notes)),
+                    show_hud.run_if(
+                        state_changed::<GameState>().or_else(resource_changed::<NotesOpen>()),
+                    ),
+                    update_dial.run_if(resource_changed::<DayTimer>()),
+                    update_gold.run_if(resource_changed::<DailyGold>()),
+                    update_rep.run_if(resource_changed::<Reputation>()),
+                )
+                    .in_set(NeedsTextSet),
+            );
+    }
+}
+
+/// Where on the dial the shadow falls, from the left edge in the morning to the right at dusk
+fn dial_point(progress: f32, radius: f32) -> Vec2 {
+    let angle = PI * (1.0 - progress.clamp(0.0, 1.0));
+    Vec2::new(
+        DIAL_WIDTH / 2.0 + radius * angle.cos(),
+        MARK_SIZE.y / 2.0 + DIAL_RADIUS - radius * angle.sin(),
+    )
+}
+
+fn hour(progress: f32) -> f32 {
+    OPENING_HOUR + (CLOSING_HOUR - OPENING_HOUR) * progress
+}
+
+fn spawn_hud(mut cmd: Commands, fonts: Res<Fonts>) {
+    let heading = TextStyle {
+        font: fonts.handwritten.clone(),
+        font_size: 20.0,
+        color: Color::BLACK,
+    };
+    let body = TextStyle {
+        font: fonts.default.clone(),
+        font_size: 16.0,
+        color: Color::BLACK,
+    };
+    let small = TextStyle {
+        font_size: 12.0,
+        ..body.clone()
+    };
+    let panel = Style {
+        position_type: PositionType::Absolute,
+        bottom: Val::Px(10.0),
+        border: UiRect::all(Val::Px(2.0)),
+        flex_direction: FlexDirection::Column,
+        row_gap: Val::Px(5.0),
+        padding: UiRect::all(Val::Px(10.0)),
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    // clock, takings and reputation
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                left: Val::Px(10.0),
+                ..panel.clone()
+            },
+            border_color: Color::BLACK.into(),
+            background_color: PARCHMENT.into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Hud,
+    ))
+    .with_children(|parent| {
+        // sundial
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(DIAL_WIDTH),
+                    height: Val::Px(DIAL_RADIUS + MARK_SIZE.y),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                // every other hour marked in numerals
+                for h in (OPENING_HOUR as u32..=CLOSING_HOUR as u32).step_by(2) {
+                    let progress = (h as f32 - OPENING_HOUR) / (CLOSING_HOUR - OPENING_HOUR);
+                    let pos = dial_point(progress, DIAL_RADIUS);
+                    parent.spawn(
+                        TextBundle::from_section(roman(h), small.clone())
+                            .with_text_alignment(TextAlignment::Center)
+                            .with_style(Style {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(pos.x - MARK_SIZE.x / 2.0),
+                                top: Val::Px(pos.y - MARK_SIZE.y / 2.0),
+                                width: Val::Px(MARK_SIZE.x),
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            }),
+                    );
+                }
+
+                for i in 0..SHADOW_DOTS {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Px(SHADOW_DOT),
+                                height: Val::Px(SHADOW_DOT),
+                                ..default()
+                            },
+                            background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                            ..default()
+                        },
+                        Shadow((i + 1) as f32 / (SHADOW_DOTS + 1) as f32),
+                    ));
+                }
+            });
+        parent.spawn((TextBundle::from_section("", body.clone()), ClockText));
+
+        parent.spawn((TextBundle::from_section("", heading.clone()), GoldText));
+
+        // reputation gauge
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(GAUGE_WIDTH),
+                    height: Val::Px(10.0),
+                    border: UiRect::all(Val::Px(1.0)),
+                    ..default()
+                },
+                border_color: Color::BLACK.into(),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    RepGauge,
+                ));
+            });
+        parent.spawn((TextBundle::from_section("", small.clone()), RepText));
+
+        parent.spawn(TextBundle::from_section(
+            "N - merchant's notes",
+            small.clone(),
+        ));
+    });
+
+    // merchant's notes, what the current order is worth to the stall
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                right: Val::Px(10.0),
+                ..panel
+            },
+            border_color: Color::BLACK.into(),
+            background_color: PARCHMENT.into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Hud,
+        Notes,
+        On::<Pointer<Down>>::run(|mut open: ResMut<NotesOpen>| **open = false),
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section("Merchant's Notes", heading.clone()),
+            Pickable::IGNORE,
+        ));
+
+        parent
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_template_columns: vec![GridTrack::flex(1.0), GridTrack::auto()],
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .with_children(|parent| {
+                note_row(parent, "Customer will pay", &body, CustText);
+                note_row(parent, "Cost of goods", &body, CostText);
+                note_row(parent, "Profit", &body, ProfitText);
+            });
+    });
+}
+
+/// A line in the notes, kept up to date by the player's systems through its marker
+fn note_row(parent: &mut ChildBuilder, label: &str, style: &TextStyle, marker: impl Component) {
+    parent.spawn((
+        TextBundle::from_section(label, style.clone()),
+        Pickable::IGNORE,
+    ));
+    parent.spawn((
+        TextBundle::from_section("", style.clone()).with_style(Style {
+            justify_self: JustifySelf::End,
+            ..default()
+        }),
+        Pickable::IGNORE,
+        marker,
+    ));
+}
+
+fn roman(hour: u32) -> &'static str {
+    const NUMERALS: [&str; 12] = [
+        "XII", "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI",
+    ];
+    NUMERALS[hour as usize % 12]
+}
+
+fn show_hud(
+    mut q: Query<(&mut Visibility, Has<Notes>), With<Hud>>,
+    state: Res<State<GameState>>,
+    open: Res<NotesOpen>,
+) {
+    let trading = matches!(
+        state.get(),
+        GameState::DayStart | GameState::Waiting | GameState::Customer
+    );
+
+    for (mut vis, notes) in q.iter_mut() {
+        *vis = if trading && (!notes || **open) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_dial(
+    mut shadow_q: Query<(&mut Style, &Shadow)>,
+    mut text_q: Query<&mut Text, With<ClockText>>,
+    day: Res<DayTimer>,
+) {
+    let progress = day.percent();
+
+    for (mut style, Shadow(along)) in shadow_q.iter_mut() {
+        let pos = dial_point(progress, DIAL_RADIUS * along);
+        style.left = Val::Px(pos.x - SHADOW_DOT / 2.0);
+        style.top = Val::Px(pos.y - SHADOW_DOT / 2.0);
+    }
+
+    let label = if day.finished() {
+        "Closing time".to_string()
+    } else {
+        let h = hour(progress).floor() as u32;
+        match h {
+            12 => "Midday".to_string(),
+            0..=11 => format!("{} in the morning", roman(h)),
+            13..=16 => format!("{} in the afternoon", roman(h)),
+            _ => format!("{} in the evening", roman(h)),
+        }
+    };
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = label.clone();
+    }
+}
+
+fn update_gold(mut q: Query<&mut Text, With<GoldText>>, gold: Res<DailyGold>) {
+    for mut text in q.iter_mut() {
+        text.sections[0].value = format!("Today: {:.0} gold", **gold);
+    }
+}
+
+fn update_rep(
+    mut gauge_q: Query<(&mut Style, &mut BackgroundColor), With<RepGauge>>,
+    mut text_q: Query<&mut Text, With<RepText>>,
+    rep: Res<Reputation>,
+) {
+    for (mut style, mut color) in gauge_q.iter_mut() {
+        style.width = Val::Percent(**rep as f32);
+        // from red when they're angry to green when they're behind the stall
+        let warmth = **rep as f32 / 100.0;
+        *color = Color::rgb(1.0 - warmth, 0.3 + 0.5 * warmth, 0.1).into();
+    }
+
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = format!("Reputation {} ({})", **rep, rep.sentiment());
+    }
+}
@@ -2,8 +2,9 @@ use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 
 use crate::{
-    assets::{Fonts, Images},
-    game::GameState,
+    assets::{Difficulties, Difficulty, Fonts, Images},
+    game::{ActiveDifficulty, GameMode, GameState},
+    save::SaveData,
     utils::despawn_all,
 };
 
@@ -15,6 +16,12 @@ struct MainMenu;
 #[derive(Component)]
 struct Help;
 
+#[derive(Component)]
+struct HighScores;
+
+#[derive(Component)]
+struct DifficultyLabel;
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, States, PartialEq, Eq, Hash, Default)]
 enum MainMenuState {
@@ -22,6 +29,7 @@ enum MainMenuState {
     Menu,
     About,
     Help,
+    HighScores,
 }
 
 pub struct MainMenuPlugin;
@@ -31,7 +39,15 @@ impl Plugin for MainMenuPlugin {
         app.add_state::<MainMenuState>()
             .add_systems(OnEnter(MainMenuState::Help), show_about)
             .add_systems(OnExit(MainMenuState::Help), despawn_all::<Help>)
+            .add_systems(OnEnter(MainMenuState::HighScores), show_high_scores)
+            .add_systems(OnExit(MainMenuState::HighScores), despawn_all::<HighScores>)
             .add_systems(OnEnter(GameState::MainMenu), spawn_menu)
+            .add_systems(
+                Update,
+                show_difficulty.run_if(
+                    in_state(GameState::MainMenu).and_then(resource_changed::<ActiveDifficulty>()),
+                ),
+            )
             .add_systems(OnExit(GameState::MainMenu), despawn_all::<MainMenu>);
     }
 }
@@ -49,7 +65,124 @@ fn show_about(mut cmd: Commands, images: Res<Images>) {
     ));
 }
 
-fn spawn_menu(mut cmd: Commands, fonts: Res<Fonts>) {
+fn show_high_scores(mut cmd: Commands, fonts: Res<Fonts>, save: Res<SaveData>) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        HighScores,
+        On::<Pointer<Down>>::run(|mut state: ResMut<NextState<MainMenuState>>| {
+            state.set(MainMenuState::Menu)
+        }),
+    ))
+    .with_children(|parent| {
+        parent
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(10.0)),
+                        align_items: AlignItems::Center,
+                        border: UiRect::all(Val::Px(2.0)),
+                        row_gap: Val::Px(4.0),
+                        width: Val::Percent(40.0),
+                        ..default()
+                    },
+                    border_color: Color::BLACK.into(),
+                    background_color: PARCHMENT.into(),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section(
+                        "High Scores",
+                        TextStyle {
+                            font: fonts.handwritten.clone(),
+                            font_size: 24.0,
+                            color: Color::BLACK,
+                        },
+                    ),
+                    Pickable::IGNORE,
+                ));
+
+                if save.high_scores.is_empty() {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "No endless runs yet",
+                            TextStyle {
+                                font: fonts.default.clone(),
+                                font_size: 20.0,
+                                color: Color::DARK_GRAY,
+                            },
+                        ),
+                        Pickable::IGNORE,
+                    ));
+                }
+
+                for (i, high) in save.high_scores.iter().enumerate() {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            format!(
+                                "{}. {:.0} ({:.0} gold over {} days)",
+                                i + 1,
+                                high.score,
+                                high.profit,
+                                high.days
+                            ),
+                            TextStyle {
+                                font: fonts.default.clone(),
+                                font_size: 20.0,
+                                color: Color::BLACK,
+                            },
+                        ),
+                        Pickable::IGNORE,
+                    ));
+                }
+            });
+    });
+}
+
+/// Switch to the preset after the active one
+fn cycle_difficulty(
+    mut active: ResMut<ActiveDifficulty>,
+    difficulties: Res<Difficulties>,
+    presets: Res<Assets<Difficulty>>,
+) {
+    let loaded: Vec<&Difficulty> = difficulties
+        .presets
+        .iter()
+        .filter_map(|h| presets.get(h))
+        .collect();
+    let current = loaded.iter().position(|d| d.name == active.name);
+    let next = current.map_or(0, |i| (i + 1) % loaded.len());
+
+    if let Some(next) = loaded.get(next) {
+        *active = ActiveDifficulty((*next).clone());
+    }
+}
+
+fn show_difficulty(
+    mut q: Query<&mut Text, With<DifficultyLabel>>,
+    difficulty: Res<ActiveDifficulty>,
+) {
+    for mut txt in q.iter_mut() {
+        txt.sections[0].value = format!("Difficulty: {}", difficulty.name);
+    }
+}
+
+fn spawn_menu(mut cmd: Commands, fonts: Res<Fonts>, difficulty: Res<ActiveDifficulty>) {
     cmd.spawn((
         NodeBundle {
             style: Style {
@@ -125,8 +258,10 @@ fn spawn_menu(mut cmd: Commands, fonts: Res<Fonts>) {
                                     ..default()
                                 },
                                 On::<Pointer<Down>>::run(
-                                    |mut state: ResMut<NextState<GameState>>| {
-                                        state.set(GameState::DayStart)
+                                    |mut mode: ResMut<GameMode>,
+                                     mut state: ResMut<NextState<GameState>>| {
+                                        *mode = GameMode::Campaign;
+                                        state.set(GameState::Reset)
                                     },
                                 ),
                                 On::<Pointer<Over>>::listener_insert(BackgroundColor(
@@ -148,6 +283,119 @@ fn spawn_menu(mut cmd: Commands, fonts: Res<Fonts>) {
                                 ));
                             });
 
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        border: UiRect::all(Val::Px(2.0)),
+                                        width: Val::Auto,
+                                        padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                        justify_content: JustifyContent::Center,
+                                        ..default()
+                                    },
+                                    border_color: Color::BLACK.into(),
+                                    background_color: Color::NONE.into(),
+                                    ..default()
+                                },
+                                On::<Pointer<Down>>::run(
+                                    |mut mode: ResMut<GameMode>,
+                                     mut state: ResMut<NextState<GameState>>| {
+                                        *mode = GameMode::Endless;
+                                        state.set(GameState::Reset)
+                                    },
+                                ),
+                                On::<Pointer<Over>>::listener_insert(BackgroundColor(
+                                    Color::ALICE_BLUE,
+                                )),
+                                On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        "Endless",
+                                        TextStyle {
+                                            font: fonts.default.clone(),
+                                            font_size: 24.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ),
+                                    Pickable::IGNORE,
+                                ));
+                            });
+
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        border: UiRect::all(Val::Px(2.0)),
+                                        width: Val::Auto,
+                                        padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                        justify_content: JustifyContent::Center,
+                                        ..default()
+                                    },
+                                    border_color: Color::BLACK.into(),
+                                    background_color: Color::NONE.into(),
+                                    ..default()
+                                },
+                                On::<Pointer<Down>>::run(cycle_difficulty),
+                                On::<Pointer<Over>>::listener_insert(BackgroundColor(
+                                    Color::ALICE_BLUE,
+                                )),
+                                On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        format!("Difficulty: {}", difficulty.name),
+                                        TextStyle {
+                                            font: fonts.default.clone(),
+                                            font_size: 24.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ),
+                                    DifficultyLabel,
+                                    Pickable::IGNORE,
+                                ));
+                            });
+
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        border: UiRect::all(Val::Px(2.0)),
+                                        width: Val::Auto,
+                                        padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                        justify_content: JustifyContent::Center,
+                                        ..default()
+                                    },
+                                    border_color: Color::BLACK.into(),
+                                    background_color: Color::NONE.into(),
+                                    ..default()
+                                },
+                                On::<Pointer<Down>>::run(
+                                    |mut state: ResMut<NextState<MainMenuState>>| {
+                                        state.set(MainMenuState::HighScores)
+                                    },
+                                ),
+                                On::<Pointer<Over>>::listener_insert(BackgroundColor(
+                                    Color::ALICE_BLUE,
+                                )),
+                                On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        "High Scores",
+                                        TextStyle {
+                                            font: fonts.default.clone(),
+                                            font_size: 24.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ),
+                                    Pickable::IGNORE,
+                                ));
+                            });
+
                         parent
                             .spawn((
                                 ButtonBundle {
@@ -4,13 +4,19 @@ use bevy::prelude::*;
 
 use crate::assets::Fonts;
 
-use self::{day_end_menu::DayEndPlugin, tooltips::TooltipPlugin, player_ui::PlayerUiPlugin, main_menu::MainMenuPlugin, game_end_menu::GameEndMenuPlugin};
+use self::{day_end_menu::DayEndPlugin, event_card::EventCardPlugin, haggle_menu::HaggleMenuPlugin, hud::HudPlugin, price_board::PriceBoardPlugin, tooltips::TooltipPlugin, player_ui::PlayerUiPlugin, main_menu::MainMenuPlugin, game_end_menu::GameEndMenuPlugin, shop_menu::ShopMenuPlugin, week_end_menu::WeekEndMenuPlugin};
 
 mod day_end_menu;
+mod event_card;
+mod haggle_menu;
+mod hud;
 mod player_ui;
+mod price_board;
 mod game_over;
 mod main_menu;
 mod game_end_menu;
+mod shop_menu;
+mod week_end_menu;
 pub mod tooltips;
 
 #[derive(States, Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
@@ -32,7 +38,7 @@ impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_state::<MenuState>()
-            .add_plugins((DayEndPlugin, TooltipPlugin, PlayerUiPlugin, MainMenuPlugin, GameEndMenuPlugin));
+            .add_plugins((DayEndPlugin, TooltipPlugin, PlayerUiPlugin, MainMenuPlugin, GameEndMenuPlugin, ShopMenuPlugin, WeekEndMenuPlugin, HaggleMenuPlugin, PriceBoardPlugin, EventCardPlugin, HudPlugin));
 
         app.configure_sets(Update, NeedsTextSet.run_if(resource_exists::<Fonts>()));
     }
@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 
-use crate::{assets::Fonts, game::{TargetWeight, GameState}, utils::Total};
+use crate::{
+    assets::Fonts,
+    game::{AgreedPrice, GameState, PriceBoard, TargetProduct, TargetWeight},
+    utils::Total,
+};
 
 use super::{NeedsTextSet, PARCHMENT};
 
@@ -17,7 +21,12 @@ impl Plugin for PlayerUiPlugin {
         app.add_systems(OnExit(GameState::Loading), spawn_ui).add_systems(
             Update,
             update_ticket
-                .run_if(resource_exists_and_changed::<TargetWeight>())
+                .run_if(
+                    resource_exists_and_changed::<TargetWeight>()
+                        .or_else(resource_exists_and_changed::<TargetProduct>())
+                        .or_else(resource_exists_and_changed::<AgreedPrice>())
+                        .or_else(resource_exists_and_changed::<PriceBoard>()),
+                )
                 .in_set(NeedsTextSet),
         );
     }
@@ -85,15 +94,19 @@ fn spawn_ui(mut cmd: Commands, fonts: Res<Fonts>) {
     });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_ticket(
     mut cmd: Commands,
     mut vis_q: Query<&mut Visibility, With<OrderTicket>>,
     q: Query<Entity, With<OrderGrid>>,
     target: Res<TargetWeight>,
+    product: Res<TargetProduct>,
+    agreed: Res<AgreedPrice>,
+    board: Res<PriceBoard>,
     fonts: Res<Fonts>,
 ) {
     if let Ok(mut vis) = vis_q.get_single_mut() {
-        *vis = if target.total() > 0.0 {
+        *vis = if target.total() > 0.0 || product.count > 0 {
             Visibility::Inherited
         } else {
             Visibility::Hidden
@@ -104,10 +117,21 @@ fn update_ticket(
         cmd.entity(ent)
             .despawn_descendants()
             .with_children(|parent| {
-                for (t, amnt) in target.iter() {
+                let goods = target
+                    .iter()
+                    .map(|(t, amnt)| (t.to_string(), t.format_amount(*amnt)));
+                let products = (product.count > 0)
+                    .then(|| (product.name.clone(), product.count.to_string()));
+                // what the customer pays, at the board prices unless haggled over
+                let price = (target.total() > 0.0 || product.count > 0).then(|| {
+                    let cost = agreed.or_list(&target, &product, &board);
+                    ("Price".to_string(), format!("{cost:.0} gold"))
+                });
+
+                for (name, amnt) in goods.chain(products).chain(price) {
                     parent.spawn(TextBundle {
                         text: Text::from_section(
-                            format!("{t} - "),
+                            format!("{name} - "),
                             TextStyle {
                                 font: fonts.handwritten.clone(),
                                 font_size: 16.0,
@@ -119,7 +143,7 @@ fn update_ticket(
 
                     parent.spawn(TextBundle {
                         text: Text::from_section(
-                            format!("{amnt:.1} g"),
+                            amnt,
                             TextStyle {
                                 font: fonts.handwritten.clone(),
                                 font_size: 16.0,
@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    assets::Fonts,
+    game::{GameState, ItemType, PriceBoard, Unit},
+    utils::despawn_all,
+};
+
+use super::{NeedsTextSet, PARCHMENT};
+
+#[derive(Component)]
+struct Board;
+
+#[derive(Component)]
+struct PriceText(ItemType);
+
+#[derive(Component)]
+struct DemandText(ItemType);
+
+pub struct PriceBoardPlugin;
+
+impl Plugin for PriceBoardPlugin {
+    fn build(&self, app: &mut App) {
+        // prices are set between days and whenever the stall is quiet
+        app.add_systems(OnEnter(GameState::Shop), spawn_board)
+            .add_systems(OnEnter(GameState::Waiting), spawn_board)
+            .add_systems(
+                Update,
+                update_board
+                    .run_if(resource_exists_and_changed::<PriceBoard>())
+                    .in_set(NeedsTextSet),
+            )
+            .add_systems(OnExit(GameState::Shop), despawn_all::<Board>)
+            .add_systems(OnExit(GameState::Waiting), despawn_all::<Board>);
+    }
+}
+
+fn spawn_board(mut cmd: Commands, fonts: Res<Fonts>, board: Res<PriceBoard>) {
+    let heading = TextStyle {
+        font: fonts.handwritten.clone(),
+        font_size: 20.0,
+        color: Color::BLACK,
+    };
+    let body = TextStyle {
+        font: fonts.default.clone(),
+        font_size: 16.0,
+        color: Color::BLACK,
+    };
+
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(10.0),
+                border: UiRect::all(Val::Px(2.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            border_color: Color::BLACK.into(),
+            background_color: PARCHMENT.into(),
+            // above the shop menu's backdrop
+            z_index: ZIndex::Global(1),
+            ..default()
+        },
+        Board,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section("Prices", heading.clone()));
+
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    display: Display::Grid,
+                    grid_template_columns: vec![
+                        GridTrack::auto(),
+                        GridTrack::auto(),
+                        GridTrack::auto(),
+                        GridTrack::auto(),
+                        GridTrack::auto(),
+                    ],
+                    column_gap: Val::Px(6.0),
+                    row_gap: Val::Px(2.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                for t in ItemType::iter() {
+                    parent.spawn(TextBundle::from_section(t.label(), heading.clone()));
+                    button(
+                        parent,
+                        "-",
+                        body.clone(),
+                        On::<Pointer<Down>>::run(move |mut board: ResMut<PriceBoard>| {
+                            board.lower(t)
+                        }),
+                    );
+                    parent.spawn((
+                        TextBundle::from_section(price_label(&board, t), body.clone()),
+                        PriceText(t),
+                    ));
+                    button(
+                        parent,
+                        "+",
+                        body.clone(),
+                        On::<Pointer<Down>>::run(move |mut board: ResMut<PriceBoard>| {
+                            board.raise(t)
+                        }),
+                    );
+                    parent.spawn((
+                        TextBundle::from_section(demand_label(&board, t), body.clone()),
+                        DemandText(t),
+                    ));
+                }
+            });
+    });
+}
+
+fn button(parent: &mut ChildBuilder, label: &str, style: TextStyle, on_down: On<Pointer<Down>>) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    border: UiRect::all(Val::Px(2.0)),
+                    padding: UiRect::axes(Val::Px(6.), Val::Px(0.)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                border_color: Color::BLACK.into(),
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            on_down,
+            On::<Pointer<Over>>::listener_insert(BackgroundColor(Color::ALICE_BLUE)),
+            On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((TextBundle::from_section(label, style), Pickable::IGNORE));
+        });
+}
+
+fn price_label(board: &PriceBoard, t: ItemType) -> String {
+    match t.unit() {
+        Unit::Grams => format!("{:.0} gold/g", board.price(t)),
+        Unit::Count => format!("{:.0} gold each", board.price(t)),
+    }
+}
+
+fn demand_label(board: &PriceBoard, t: ItemType) -> &'static str {
+    match board.demand(t) {
+        d if d >= 1.5 => "selling fast",
+        d if d > 0.75 => "steady",
+        d if d > 0.3 => "slow",
+        _ => "nobody's buying",
+    }
+}
+
+fn update_board(
+    board: Res<PriceBoard>,
+    mut price_q: Query<(&mut Text, &PriceText), Without<DemandText>>,
+    mut demand_q: Query<(&mut Text, &DemandText), Without<PriceText>>,
+) {
+    for (mut text, PriceText(t)) in price_q.iter_mut() {
+        text.sections[0].value = price_label(&board, *t);
+    }
+
+    for (mut text, DemandText(t)) in demand_q.iter_mut() {
+        text.sections[0].value = demand_label(&board, *t).to_string();
+    }
+}
@@ -0,0 +1,228 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    assets::{Fonts, GameData, UpgradeList},
+    game::{spendable, BuyUpgrade, GameState, TotalExpenses, TotalGold},
+    save::SaveData,
+    utils::despawn_all,
+};
+
+use super::PARCHMENT;
+
+#[derive(Component)]
+struct Menu;
+
+pub struct ShopMenuPlugin;
+
+impl Plugin for ShopMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Shop), spawn_menu)
+            .add_systems(
+                Update,
+                // rebuild the listing whenever something was bought
+                (despawn_all::<Menu>, spawn_menu)
+                    .chain()
+                    .run_if(in_state(GameState::Shop).and_then(resource_changed::<SaveData>())),
+            )
+            .add_systems(OnExit(GameState::Shop), despawn_all::<Menu>);
+    }
+}
+
+fn spawn_menu(
+    mut cmd: Commands,
+    fonts: Res<Fonts>,
+    gold: Res<TotalGold>,
+    expenses: Res<TotalExpenses>,
+    save: Res<SaveData>,
+    data: Res<GameData>,
+    lists: Res<Assets<UpgradeList>>,
+) {
+    let Some(list) = lists.get(&data.upgrades) else {
+        error!("Upgrade list asset was missing");
+        return;
+    };
+    let funds = spendable(&gold, &expenses);
+
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::BLACK.into(),
+            ..default()
+        },
+        Menu,
+    ))
+    .with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)),
+                    row_gap: Val::Px(4.0),
+                    width: Val::Percent(60.0),
+                    ..default()
+                },
+                border_color: Color::BLACK.into(),
+                background_color: PARCHMENT.into(),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    "Upgrades",
+                    TextStyle {
+                        font: fonts.handwritten.clone(),
+                        font_size: 24.0,
+                        color: Color::BLACK,
+                    },
+                ));
+
+                parent.spawn(TextBundle::from_section(
+                    format!("{funds:.0} gold to spend"),
+                    TextStyle {
+                        font: fonts.default.clone(),
+                        font_size: 20.0,
+                        color: Color::BLACK,
+                    },
+                ));
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            display: Display::Grid,
+                            grid_template_columns: vec![GridTrack::fr(1.0), GridTrack::auto()],
+                            row_gap: Val::Px(6.0),
+                            column_gap: Val::Px(10.0),
+                            margin: UiRect::vertical(Val::Px(10.0)),
+                            width: Val::Percent(100.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for upgrade in list.upgrades.iter() {
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        flex_direction: FlexDirection::Column,
+                                        ..default()
+                                    },
+                                    ..default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        &upgrade.name,
+                                        TextStyle {
+                                            font: fonts.handwritten.clone(),
+                                            font_size: 20.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ));
+                                    parent.spawn(TextBundle::from_section(
+                                        &upgrade.description,
+                                        TextStyle {
+                                            font: fonts.default.clone(),
+                                            font_size: 14.0,
+                                            color: Color::DARK_GRAY,
+                                        },
+                                    ));
+                                });
+
+                            let owned = save.owns(&upgrade.id);
+                            let label = if owned {
+                                "Owned".to_string()
+                            } else {
+                                format!("{} gold", upgrade.cost)
+                            };
+                            let color = if owned || funds < upgrade.cost {
+                                Color::GRAY
+                            } else {
+                                Color::BLACK
+                            };
+
+                            let id = upgrade.id.clone();
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        style: Style {
+                                            border: UiRect::all(Val::Px(2.0)),
+                                            padding: UiRect::axes(Val::Px(10.), Val::Px(5.)),
+                                            justify_content: JustifyContent::Center,
+                                            align_self: AlignSelf::Center,
+                                            ..default()
+                                        },
+                                        border_color: color.into(),
+                                        background_color: Color::NONE.into(),
+                                        ..default()
+                                    },
+                                    On::<Pointer<Down>>::run(
+                                        move |mut ew: EventWriter<BuyUpgrade>| {
+                                            ew.send(BuyUpgrade(id.clone()))
+                                        },
+                                    ),
+                                    On::<Pointer<Over>>::listener_insert(BackgroundColor(
+                                        Color::ALICE_BLUE,
+                                    )),
+                                    On::<Pointer<Out>>::listener_insert(BackgroundColor(
+                                        Color::NONE,
+                                    )),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        TextBundle::from_section(
+                                            label,
+                                            TextStyle {
+                                                font: fonts.default.clone(),
+                                                font_size: 18.0,
+                                                color,
+                                            },
+                                        ),
+                                        Pickable::IGNORE,
+                                    ));
+                                });
+                        }
+                    });
+
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                border: UiRect::all(Val::Px(2.0)),
+                                width: Val::Auto,
+                                padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            },
+                            border_color: Color::BLACK.into(),
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        On::<Pointer<Down>>::run(|mut state: ResMut<NextState<GameState>>| {
+                            state.set(GameState::DayStart)
+                        }),
+                        On::<Pointer<Over>>::listener_insert(BackgroundColor(Color::ALICE_BLUE)),
+                        On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "Open shop",
+                                TextStyle {
+                                    font: fonts.default.clone(),
+                                    font_size: 24.0,
+                                    color: Color::BLACK,
+                                },
+                            ),
+                            Pickable::IGNORE,
+                        ));
+                    });
+            });
+    });
+}
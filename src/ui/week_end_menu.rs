@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    assets::Fonts,
+    game::{ActiveDifficulty, CurrentWeek, GameMode, GameState, NextWeek, WeekProfit},
+    utils::despawn_all,
+};
+
+use super::PARCHMENT;
+
+#[derive(Component)]
+struct Menu;
+
+pub struct WeekEndMenuPlugin;
+
+impl Plugin for WeekEndMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::WeekEnd), spawn_menu)
+            .add_systems(OnExit(GameState::WeekEnd), despawn_all::<Menu>);
+    }
+}
+
+fn spawn_menu(
+    mut cmd: Commands,
+    fonts: Res<Fonts>,
+    week: CurrentWeek,
+    profit: Res<WeekProfit>,
+    mode: Res<GameMode>,
+    difficulty: Res<ActiveDifficulty>,
+) {
+    let Some(current) = week.get() else {
+        error!("Campaign asset was missing");
+        return;
+    };
+    let rent_factor = difficulty.rent_factor;
+
+    let title = TextStyle {
+        font: fonts.handwritten.clone(),
+        font_size: 24.0,
+        color: Color::BLACK,
+    };
+    let heading = TextStyle {
+        font: fonts.handwritten.clone(),
+        font_size: 20.0,
+        color: Color::BLACK,
+    };
+    let body = TextStyle {
+        font: fonts.default.clone(),
+        font_size: 20.0,
+        color: Color::BLACK,
+    };
+
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::BLACK.into(),
+            ..default()
+        },
+        Menu,
+    ))
+    .with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)),
+                    row_gap: Val::Px(4.0),
+                    width: Val::Percent(50.0),
+                    ..default()
+                },
+                border_color: Color::BLACK.into(),
+                background_color: PARCHMENT.into(),
+                ..default()
+            })
+            .with_children(|parent| {
+                let (heading_text, summary) = match *mode {
+                    GameMode::Campaign => (
+                        format!("Week {}: {} is over", week.number(), current.name),
+                        format!(
+                            "Made {:.0} of the {:.0} gold you were after",
+                            **profit, current.goal
+                        ),
+                    ),
+                    GameMode::Endless => (
+                        "Another week survived".to_string(),
+                        format!("Made {:.0} gold this week", **profit),
+                    ),
+                };
+                parent.spawn(TextBundle::from_section(heading_text, title.clone()));
+                parent.spawn(TextBundle::from_section(summary, body.clone()));
+
+                // horizontal rule
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        height: Val::Px(3.0),
+                        width: Val::Percent(75.0),
+                        margin: UiRect::vertical(Val::Px(5.0)),
+                        ..default()
+                    },
+                    background_color: Color::BLACK.into(),
+                    ..default()
+                });
+
+                // endless runs keep coming back round to the same week
+                let next = match *mode {
+                    GameMode::Campaign => week.next(),
+                    GameMode::Endless => Some(current),
+                };
+                let label = match next {
+                    Some(next) if *mode == GameMode::Endless => {
+                        parent.spawn(TextBundle::from_section(
+                            format!(
+                                "{} more days, with {:.0} gold rent due at the end",
+                                next.days,
+                                next.rent * rent_factor
+                            ),
+                            body.clone(),
+                        ));
+                        "Next week"
+                    }
+                    Some(next) => {
+                        parent.spawn(TextBundle::from_section(
+                            format!("Next up, week {}: {}", week.number() + 1, next.name),
+                            heading.clone(),
+                        ));
+                        parent.spawn(TextBundle::from_section(
+                            format!(
+                                "{} days to make {:.0} gold, with {:.0} gold rent due at the end",
+                                next.days,
+                                next.goal,
+                                next.rent * rent_factor
+                            ),
+                            body.clone(),
+                        ));
+                        if !next.introduces.is_empty() {
+                            parent.spawn(TextBundle::from_section(
+                                format!("New faces at the market: {}", next.introduces.join(", ")),
+                                body.clone(),
+                            ));
+                        }
+                        "Next week"
+                    }
+                    None => {
+                        parent.spawn(TextBundle::from_section(
+                            "That was the last of it, the stall is yours for good",
+                            heading.clone(),
+                        ));
+                        "Finish"
+                    }
+                };
+
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                border: UiRect::all(Val::Px(2.0)),
+                                width: Val::Auto,
+                                padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                margin: UiRect::top(Val::Px(20.0)),
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            },
+                            border_color: Color::BLACK.into(),
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        On::<Pointer<Down>>::send_event::<NextWeek>(),
+                        On::<Pointer<Over>>::listener_insert(BackgroundColor(Color::ALICE_BLUE)),
+                        On::<Pointer<Out>>::listener_insert(BackgroundColor(Color::NONE)),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                label,
+                                TextStyle {
+                                    font_size: 24.0,
+                                    ..body.clone()
+                                },
+                            ),
+                            Pickable::IGNORE,
+                        ));
+                    });
+            });
+    });
+}
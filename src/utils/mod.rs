@@ -10,7 +10,7 @@ use bevy_tweening::*;
 
 use crate::{
     assets::Fonts,
-    game::{Advance, ItemType, ITEM_COST},
+    game::{Advance, ItemType, Unit, ITEM_COST},
     input::Action,
 };
 
@@ -194,9 +194,6 @@ impl Approx for f32 {
 
 pub trait CalcCost {
     fn cost(&self) -> f32;
-    fn customer_cost(&self) -> f32 {
-        self.cost() * 2.0
-    }
 }
 
 impl CalcCost for HashMap<ItemType, f32> {
@@ -207,6 +204,29 @@ impl CalcCost for HashMap<ItemType, f32> {
     }
 }
 
+pub trait Units {
+    /// Only the goods that go on the scale
+    fn weighed(&self) -> Self;
+    /// Only the goods that are counted out on the counter
+    fn counted(&self) -> Self;
+}
+
+impl Units for HashMap<ItemType, f32> {
+    fn weighed(&self) -> Self {
+        self.iter()
+            .filter(|(t, _)| t.unit() == Unit::Grams)
+            .map(|(t, amnt)| (*t, *amnt))
+            .collect()
+    }
+
+    fn counted(&self) -> Self {
+        self.iter()
+            .filter(|(t, _)| t.unit() == Unit::Count)
+            .map(|(t, amnt)| (*t, *amnt))
+            .collect()
+    }
+}
+
 pub trait Ratios {
     type Output;
     fn ratio(&self) -> Self::Output;
@@ -1,12 +1,35 @@
+use std::f32::consts::PI;
+
 use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*};
 
-use crate::{assets::Meshes, game::GameState};
+use crate::{
+    assets::{DayLighting, GameData, Meshes},
+    game::{DayTimer, GameState, Weather},
+    player::Player,
+};
+
+const LANTERN_INTENSITY: f32 = 200.0;
+/// How far round the sky the sun swings either side of noon, in radians
+const SUN_SWING: f32 = PI / 3.0;
+const SUN_DISTANCE: f32 = 10.0;
+
+#[derive(Component)]
+struct Sun;
+
+#[derive(Component)]
+struct Lantern;
 
 pub struct WorldPlugin;
 
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::Loading), spawn_world);
+        app.add_systems(OnExit(GameState::Loading), spawn_world)
+            .add_systems(
+                Update,
+                follow_the_day.run_if(resource_exists::<GameData>().and_then(
+                    resource_changed::<DayTimer>().or_else(resource_changed::<Weather>()),
+                )),
+            );
     }
 }
 
@@ -52,32 +75,91 @@ fn spawn_world(
         color: Color::WHITE,
         brightness: 0.2,
     });
-    cmd.spawn(PointLightBundle {
-        point_light: PointLight {
-            intensity: 200.0,
-            radius: 0.05,
-            range: 0.1,
-            color: Color::ORANGE_RED,
-            shadows_enabled: true,
+    cmd.spawn((
+        PointLightBundle {
+            point_light: PointLight {
+                intensity: 0.0,
+                radius: 0.05,
+                range: 0.1,
+                color: Color::ORANGE_RED,
+                shadows_enabled: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(-1.0, 2.0, 2.0),
             ..default()
         },
-        transform: Transform::from_xyz(-1.0, 2.0, 2.0),
-        ..default()
-    });
-    cmd.spawn(DirectionalLightBundle {
-        directional_light: DirectionalLight {
-            shadows_enabled: true,
-            illuminance: 10000.0,
+        Lantern,
+    ));
+    cmd.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                shadows_enabled: true,
+                illuminance: 10000.0,
+                ..default()
+            },
+            cascade_shadow_config: CascadeShadowConfigBuilder {
+                first_cascade_far_bound: 4.0,
+                maximum_distance: 10.0,
+                ..default()
+            }
+            .into(),
+            transform: Transform::from_xyz(0.0, 10.0, -10.0)
+                .looking_at(Vec3::new(1.0, 0.0, 0.0), Vec3::Y),
             ..default()
         },
-        cascade_shadow_config: CascadeShadowConfigBuilder {
-            first_cascade_far_bound: 4.0,
-            maximum_distance: 10.0,
-            ..default()
-        }
-        .into(),
-        transform: Transform::from_xyz(0.0, 10.0, -10.0)
-            .looking_at(Vec3::new(1.0, 0.0, 0.0), Vec3::Y),
-        ..default()
-    });
+        Sun,
+    ));
+}
+
+/// Moves the sun across the sky over the day and lights the lantern at dusk, clouds dim it all
+#[allow(clippy::too_many_arguments)]
+fn follow_the_day(
+    day: Res<DayTimer>,
+    weather: Res<Weather>,
+    data: Res<GameData>,
+    lightings: Res<Assets<DayLighting>>,
+    mut ambient: ResMut<AmbientLight>,
+    mut sun_q: Query<(&mut DirectionalLight, &mut Transform), With<Sun>>,
+    mut lantern_q: Query<&mut PointLight, With<Lantern>>,
+    mut fog_q: Query<&mut FogSettings, With<Player>>,
+) {
+    let Some(lighting) = lightings.get(&data.lighting) else {
+        return;
+    };
+    let progress = day.percent();
+    let Some(key) = lighting.at(progress) else {
+        return;
+    };
+
+    // rising on one side of the road and setting on the other
+    let swing = SUN_SWING * (2.0 * progress - 1.0);
+    let height = key.sun_height.to_radians();
+    let sun_pos = Vec3::new(
+        swing.sin() * height.cos(),
+        height.sin(),
+        -swing.cos() * height.cos(),
+    ) * SUN_DISTANCE;
+
+    for (mut light, mut tr) in sun_q.iter_mut() {
+        light.color = key.sun_color;
+        light.illuminance = key.illuminance * weather.daylight();
+        *tr = Transform::from_translation(sun_pos).looking_at(Vec3::ZERO, Vec3::Y);
+    }
+
+    ambient.brightness = key.ambient * weather.daylight();
+
+    for mut fog in fog_q.iter_mut() {
+        fog.color = key.fog_color;
+        fog.falloff = FogFalloff::ExponentialSquared {
+            density: key.fog_density + weather.haze(),
+        };
+    }
+
+    for mut lantern in lantern_q.iter_mut() {
+        lantern.intensity = if progress >= lighting.lantern {
+            LANTERN_INTENSITY
+        } else {
+            0.0
+        };
+    }
 }